@@ -1,6 +1,12 @@
 use crate::{
     collection::{check_legality, check_missing, FormatLegal},
-    database::scryfall::{get_min_price, match_card, min_price_fmt, serialize_database},
+    database::{
+        history::trend_fmt,
+        scryfall::{
+            get_min_price, make_safe_name, match_card, min_price_fmt, serialize_database, PriceType,
+        },
+        sets::serialize_sets,
+    },
     startup::{
         config_check, database_check, database_management, directory_check, dl_scryfall_latest,
         load_database_file, ConfigCheck, DatabaseCheck, DatabaseType, DirectoryCheck,
@@ -9,17 +15,49 @@ use crate::{
 use arboard::Clipboard;
 use async_std::task;
 use directories_next::ProjectDirs;
-use std::{fs, io, thread, time::Duration};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
-use ratatui::{widgets::ScrollbarState, Frame};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::{
+    layout::{Position, Rect},
+    widgets::{ListState, ScrollbarState},
+    Frame,
+};
 use ratatui_explorer::{File, FileExplorer};
 
 use crate::{
-    collection::{find_missing_cards, read_decklist, read_moxfield_collection, CollectionCard},
+    collection::{
+        find_missing_cards, read_collection_printings, read_deck_labels, read_decklist,
+        squash_collection, CollectionCard, CollectionSource, DeckSection,
+    },
+    commander::{check_commander, CommanderCheck},
+    completion::{set_completion, RarityFilter, SetCompletion},
     config::DecklistConfig,
+    diff::{deck_diff, DeckDiff},
+    editor::{add_card, change_quantity, decklist_string, move_card, rename_card},
+    filter::{find_matches, ListQuery, FILTER_HELP},
+    keybindings::{parse_key, Action, KeyBindings},
+    missing::{apply_order, missing_list_string, missing_order, MissingSort},
+    odds::{sample_hands, HAND_SIZE},
+    report::shopping_report,
+    search::{search_database, SearchQuery, SearchResult, SEARCH_HELP},
     startup::{create_config, create_data_directory, create_directory},
-    tui::core::{ui, MenuTabs, Tui},
+    stats::{deck_stats, DeckStats},
+    substitute::{find_substitutes, target_format, Substitute},
+    trade::{find_surplus, trade_list_string, TradeCard},
+    tui::{
+        autocomplete::{InputAction, NameIndex, NameInput},
+        core::{tab_at, ui, MenuTabs, Tui},
+    },
+    value::{collection_value, write_value_csv, CollectionValue},
 };
 
 #[derive(Debug, Default)]
@@ -31,9 +69,20 @@ pub enum SupportedOS {
     Unsupported,
 }
 
+/// substitutes found for a missing card in the target format
+/// counter is the substitutes_counter the search started with
+pub struct SubstitutesMessage {
+    pub counter: u64,
+    pub name: String,
+    pub format: Option<String>,
+    pub substitutes: Vec<Substitute>,
+}
+
 pub struct CollectionMessage {
     pub debug: String,
     pub collection: Option<Vec<CollectionCard>>,
+    pub printings: Option<Vec<CollectionCard>>,
+    pub source: CollectionSource,
     pub status: String,
     pub exist: bool,
     pub filename: Option<String>,
@@ -44,6 +93,8 @@ impl Default for CollectionMessage {
         CollectionMessage {
             debug: String::new(),
             collection: None,
+            printings: None,
+            source: CollectionSource::Paper,
             status: String::new(),
             exist: false,
             filename: None,
@@ -65,6 +116,20 @@ impl Default for DecklistMessage {
     }
 }
 
+/// rows moved by one step of the mouse wheel
+const SCROLL_ROWS: usize = 3;
+
+/// rows moved by PageUp/PageDown on tabs without a list
+const PAGE_ROWS: usize = 10;
+
+/// what a card name typed in the decklist editor is for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeckInputMode {
+    Add,
+    Rename,
+    SaveAs, // a file path, for a decklist that wasn't loaded from a file
+}
+
 pub struct App {
     exit: bool,
     pub startup: bool,
@@ -107,6 +172,9 @@ pub struct App {
         std::sync::mpsc::Receiver<String>,
     ),
     pub collection: Option<Vec<CollectionCard>>,
+    pub collection_printings: Option<Vec<CollectionCard>>, // collection before squashing
+    pub collection_path: Option<String>,                   // full path of loaded collection
+    pub collection_source: CollectionSource,
     pub collection_file_name: Option<String>,
     pub collection_file: Option<File>,
     pub decklist: Option<Vec<CollectionCard>>,
@@ -134,10 +202,13 @@ pub struct App {
     pub price_done: bool,
     pub missing_scroll: usize,
     pub missing_scroll_state: ScrollbarState,
-    pub collection_scroll: usize,
+    pub missing_list_state: ListState,
+    pub collection_scroll: usize, // also the highlighted card
     pub collection_scroll_state: ScrollbarState,
+    pub collection_list_state: ListState,
     pub decklist_scroll: usize,
     pub decklist_scroll_state: ScrollbarState,
+    pub decklist_list_state: ListState,
     pub redraw: bool,
     pub collection_channel: (
         std::sync::mpsc::Sender<CollectionMessage>,
@@ -166,6 +237,11 @@ pub struct App {
         std::sync::mpsc::Sender<FormatLegal>,
         std::sync::mpsc::Receiver<FormatLegal>,
     ),
+    pub commander_check: Option<CommanderCheck>, // found with the legality, None without a commander
+    pub commander_msg: (
+        std::sync::mpsc::Sender<Option<CommanderCheck>>,
+        std::sync::mpsc::Receiver<Option<CommanderCheck>>,
+    ),
     pub directory_counter: u64,
     pub config_counter: u64,
     pub database_counter: u64,
@@ -175,10 +251,95 @@ pub struct App {
     pub missing_counter: u64,
     pub price_counter: u64,
     pub short_counter: u64,
+    pub value_counter: u64,
+    pub collection_value: Option<CollectionValue>,
+    pub value_started: bool,
+    pub value_done: bool,
+    pub value_msg: (
+        std::sync::mpsc::Sender<CollectionValue>,
+        std::sync::mpsc::Receiver<CollectionValue>,
+    ),
+    pub value_scroll: usize,
+    pub value_status: String,
+    pub trade_view: bool, // show the trade list instead of the value report
+    pub trade_cards: Option<Vec<TradeCard>>,
+    pub trade_started: bool,
+    pub trade_done: bool,
+    pub trade_msg: (
+        std::sync::mpsc::Sender<Vec<TradeCard>>,
+        std::sync::mpsc::Receiver<Vec<TradeCard>>,
+    ),
+    pub trade_scroll: usize,
+    pub trade_counter: u64,
+    pub set_completion: Option<Vec<SetCompletion>>,
+    pub sets_started: bool,
+    pub sets_done: bool,
+    pub sets_msg: (
+        std::sync::mpsc::Sender<Vec<SetCompletion>>,
+        std::sync::mpsc::Receiver<Vec<SetCompletion>>,
+    ),
+    pub sets_selected: usize,
+    pub sets_scroll: usize, // first missing printing shown for the selected set
+    pub rarity_filter: RarityFilter,
+    pub sets_counter: u64,
+    pub search_input: NameInput,
+    pub card_names: NameIndex, // names in the card database for suggestions, built when it loads
+    pub search_editing: bool,  // key presses go to the search box instead of the app
+    pub search_results: Option<Vec<SearchResult>>,
+    pub search_started: bool,
+    pub search_done: bool,
+    pub search_msg: (
+        std::sync::mpsc::Sender<Vec<SearchResult>>,
+        std::sync::mpsc::Receiver<Vec<SearchResult>>,
+    ),
+    pub search_scroll: usize,
+    pub search_status: String,
+    pub search_counter: u64,
+    pub list_query_input: NameInput, // text typed after / on the Collection, Deck or Missing list
+    pub list_query_editing: bool,
+    pub list_query_tab: MenuTabs, // list the query was typed on
+    pub list_query_status: String,
+    pub list_matches: Vec<usize>,
+    pub missing_sort: MissingSort,
+    pub wildcard_mode: bool, // Missing tab counts Arena wildcards instead of prices
+    pub deck_stats: Option<DeckStats>,
+    pub stats_started: bool,
+    pub stats_done: bool,
+    pub stats_msg: (
+        std::sync::mpsc::Sender<DeckStats>,
+        std::sync::mpsc::Receiver<DeckStats>,
+    ),
+    pub stats_view: bool, // show deck stats instead of the card details on the Deck tab
+    pub stats_counter: u64,
+    pub sample_seed: u64, // seed of the last sample hand, shown so a hand can be repeated
+    pub sample_hand: Option<Vec<String>>,
+    pub diff_base: Option<Vec<CollectionCard>>, // old version of the deck to compare to
+    pub diff_base_name: String,
+    pub deck_diff: Option<DeckDiff>,
+    pub substitutes: Option<(String, Option<String>, Vec<Substitute>)>, // card, target format and its substitutes
+    pub substitutes_started: bool,
+    pub substitutes_done: bool,
+    pub substitutes_counter: u64, // bumped when substitutes are cleared, so older results are dropped
+    pub substitutes_msg: (
+        std::sync::mpsc::Sender<SubstitutesMessage>,
+        std::sync::mpsc::Receiver<SubstitutesMessage>,
+    ),
+    pub decklist_path: Option<PathBuf>, // file the loaded decklist came from, for saving edits
+    pub deck_editing: bool,
+    pub deck_input_mode: Option<DeckInputMode>, // typing a card name in the editor
+    pub deck_name_input: NameInput,
+    pub deck_edit_status: String,
+    pub deck_unsaved: bool,
+    pub deck_changed: bool, // the checks re-run once the running missing/price threads are done
+    pub key_map: HashMap<KeyCode, Action>, // built from the config's key bindings
+    pub key_errors: Vec<String>,
+    pub tabs_area: Rect, // where the tab bar and the current list were drawn, for mouse clicks
+    pub list_area: Rect,
 }
 
 impl Default for App {
     fn default() -> Self {
+        let (key_map, key_errors) = KeyBindings::default().key_map();
         Self {
             exit: false,
             startup: false,
@@ -209,6 +370,9 @@ impl Default for App {
             dc: DatabaseCheck::default(),
             short_channel: std::sync::mpsc::channel(),
             collection: None,
+            collection_printings: None,
+            collection_path: None,
+            collection_source: CollectionSource::Paper,
             collection_file_name: None,
             collection_file: None,
             decklist: None,
@@ -227,10 +391,13 @@ impl Default for App {
             price_done: false,
             missing_scroll: 0,
             missing_scroll_state: ScrollbarState::default(),
+            missing_list_state: ListState::default(),
             collection_scroll: 0,
             collection_scroll_state: ScrollbarState::default(),
+            collection_list_state: ListState::default(),
             decklist_scroll: 0,
             decklist_scroll_state: ScrollbarState::default(),
+            decklist_list_state: ListState::default(),
             redraw: true,
             collection_channel: std::sync::mpsc::channel(),
             decklist_channel: std::sync::mpsc::channel(),
@@ -247,6 +414,8 @@ impl Default for App {
             legal_started: false,
             legal_done: false,
             legal_msg: std::sync::mpsc::channel(),
+            commander_check: None,
+            commander_msg: std::sync::mpsc::channel(),
             directory_counter: 0,
             config_counter: 0,
             database_counter: 0,
@@ -256,6 +425,72 @@ impl Default for App {
             missing_counter: 0,
             price_counter: 0,
             short_counter: 0,
+            value_counter: 0,
+            collection_value: None,
+            value_started: false,
+            value_done: false,
+            value_msg: std::sync::mpsc::channel(),
+            value_scroll: 0,
+            value_status: String::new(),
+            trade_view: false,
+            trade_cards: None,
+            trade_started: false,
+            trade_done: false,
+            trade_msg: std::sync::mpsc::channel(),
+            trade_scroll: 0,
+            trade_counter: 0,
+            set_completion: None,
+            sets_started: false,
+            sets_done: false,
+            sets_msg: std::sync::mpsc::channel(),
+            sets_selected: 0,
+            sets_scroll: 0,
+            rarity_filter: RarityFilter::default(),
+            sets_counter: 0,
+            search_input: NameInput::query(true),
+            card_names: NameIndex::default(),
+            search_editing: false,
+            search_results: None,
+            search_started: false,
+            search_done: false,
+            search_msg: std::sync::mpsc::channel(),
+            search_scroll: 0,
+            search_status: String::new(),
+            search_counter: 0,
+            list_query_input: NameInput::query(false),
+            list_query_editing: false,
+            list_query_tab: MenuTabs::default(),
+            list_query_status: String::new(),
+            list_matches: Vec::new(),
+            missing_sort: MissingSort::default(),
+            wildcard_mode: false,
+            deck_stats: None,
+            stats_started: false,
+            stats_done: false,
+            stats_msg: std::sync::mpsc::channel(),
+            stats_view: false,
+            stats_counter: 0,
+            sample_seed: 0,
+            sample_hand: None,
+            diff_base: None,
+            diff_base_name: String::new(),
+            deck_diff: None,
+            substitutes: None,
+            substitutes_started: false,
+            substitutes_done: false,
+            substitutes_counter: 0,
+            substitutes_msg: std::sync::mpsc::channel(),
+            decklist_path: None,
+            deck_editing: false,
+            deck_input_mode: None,
+            deck_name_input: NameInput::name(),
+            deck_edit_status: String::new(),
+            deck_unsaved: false,
+            deck_changed: false,
+            tabs_area: Rect::default(),
+            list_area: Rect::default(),
+            key_map,
+            key_errors,
         }
     }
 }
@@ -312,6 +547,10 @@ impl App {
                     self.config_exist = cc.config_exists;
                     self.config_status = cc.config_status;
                     self.config = cc.config.clone();
+                    (self.key_map, self.key_errors) = self.config.keybindings.key_map();
+                    for error in self.key_errors.iter() {
+                        self.debug_string += &format!("{}\n", error);
+                    }
                     // take care of use_database = false
                     if !cc.config.use_database {
                         self.dc.database_status =
@@ -350,12 +589,18 @@ impl App {
                 self.collection_counter += 1;
                 thread::spawn(move || {
                     let read_result =
-                        task::block_on(read_moxfield_collection(collection_path.clone()));
+                        task::block_on(read_collection_printings(collection_path.clone()));
                     let mut message = CollectionMessage::default();
                     match read_result {
-                        Ok(collection) => {
-                            message.debug += &format!("read {} successfully\n", collection_path);
-                            message.collection = Some(collection);
+                        Ok((printings, source)) => {
+                            message.debug += &format!(
+                                "read {} successfully as a {} collection\n",
+                                collection_path,
+                                source.label()
+                            );
+                            message.collection = Some(squash_collection(printings.clone()));
+                            message.printings = Some(printings);
+                            message.source = source;
                             message.status =
                                 format!("Collection loaded successfully: {}", collection_path);
                             message.exist = true;
@@ -455,6 +700,11 @@ impl App {
             if self.load_started && !self.load_done {
                 if let Ok(dc) = self.database_channel.1.try_recv() {
                     self.dc = dc;
+                    self.card_names =
+                        NameIndex::new(self.dc.database_cards.values().map(|c| c.name.as_str()));
+                    self.deck_diff = None; // picks up the prices
+                    self.substitutes = None;
+                    self.substitutes_counter += 1;
                     self.load_done = true;
                     self.load_started = false;
                     self.database_ok = !self.dc.database_cards.is_empty();
@@ -473,6 +723,7 @@ impl App {
             // don't overwrite database if loading fails
             {
                 let map = self.dc.database_cards.clone();
+                let sets = self.dc.sets.clone();
                 let path = self.dc.database_path.clone();
                 // TODO: reset this when loading a new database
                 self.short_started = true;
@@ -480,14 +731,19 @@ impl App {
                 // loaded instead of a Scryfall file
                 let short_channel = self.short_channel.0.clone();
                 self.short_counter += 1;
-                thread::spawn(
-                    move || match task::block_on(serialize_database(&map, path)) {
-                        Ok(()) => short_channel.send(
+                thread::spawn(move || {
+                    let sets_result = task::block_on(serialize_sets(&sets, path.clone()));
+                    match task::block_on(serialize_database(&map, path)) {
+                        Ok(()) if sets_result.is_ok() => short_channel.send(
                             "Compact decklist database generated successfully.\n".to_string(),
                         ),
+                        Ok(()) => short_channel.send(format!(
+                            "Compact decklist database generated, but the set index failed: {}\n",
+                            sets_result.unwrap_err()
+                        )),
                         Err(e) => short_channel.send(e.to_string()),
-                    },
-                );
+                    }
+                });
             }
             if self.short_started && !self.short_done {
                 if let Ok(s) = self.debug_channel.1.try_recv() {
@@ -504,40 +760,30 @@ impl App {
                 if let Ok(msg) = self.collection_channel.1.try_recv() {
                     self.debug_string += &msg.debug;
                     self.collection = msg.collection;
+                    self.deck_diff = None;
+                    self.substitutes = None;
+                    self.substitutes_counter += 1;
+                    self.collection_scroll = 0;
+                    self.collection_scroll_state = self.collection_scroll_state.position(0);
+                    if self.list_query_tab == MenuTabs::Collection {
+                        self.list_matches.clear();
+                    }
+                    self.collection_printings = msg.printings;
+                    self.collection_source = msg.source;
+                    if msg.source == CollectionSource::Arena {
+                        self.wildcard_mode = true;
+                        // rarity sorting needs the database, like w_press
+                        if !self.dc.database_cards.is_empty() {
+                            self.missing_sort = MissingSort::Rarity;
+                            sort_missing(self);
+                        }
+                    }
                     self.collection_status = msg.status;
                     self.collection_exist = msg.exist;
+                    self.collection_path = msg.filename.clone();
                     self.collection_file_name = msg.filename;
                     self.loading_collection = false;
-                    if self.collection.is_some()
-                        && self.decklist.is_some()
-                        && !self.waiting_for_missing
-                    {
-                        self.debug_string += "starting missing cards thread...\n";
-                        let missing_channel = self.missing_msg.0.clone();
-                        let check_channel = self.missing_check_msg.0.clone();
-                        let collection = self.collection.clone().unwrap();
-                        let decklist = self.decklist.clone().unwrap();
-                        let database = self.dc.database_cards.clone();
-                        self.missing_counter += 1;
-                        thread::spawn(move || {
-                            let missing_cards =
-                                task::block_on(find_missing_cards(collection, decklist));
-                            let mut checks = Vec::new();
-                            if missing_cards.is_some() {
-                                for card in missing_cards.as_ref().unwrap() {
-                                    let missing_str = if !database.is_empty() {
-                                        check_missing(&database, card)
-                                    } else {
-                                        "".to_string()
-                                    };
-                                    checks.push(format!("{}{}", card, missing_str));
-                                }
-                            }
-                            if let Ok(()) = check_channel.send(checks) {};
-                            if let Ok(()) = missing_channel.send(missing_cards) {};
-                        });
-                        self.waiting_for_missing = true;
-                    }
+                    self.start_missing();
                     self.loading_collection = false;
                     self.redraw = true;
                     // prompt user to save collection path to config
@@ -547,38 +793,14 @@ impl App {
             if self.loading_decklist {
                 if let Ok(msg) = self.decklist_channel.1.try_recv() {
                     self.decklist = msg.decklist;
+                    self.decklist_scroll = 0;
+                    self.decklist_scroll_state = self.decklist_scroll_state.position(0);
+                    if self.list_query_tab == MenuTabs::Deck {
+                        self.list_matches.clear();
+                    }
                     self.decklist_status = msg.status;
                     self.loading_decklist = false;
-                    if self.collection.is_some()
-                        && self.decklist.is_some()
-                        && !self.waiting_for_missing
-                    {
-                        self.debug_string += "starting missing cards thread...\n";
-                        let missing_channel = self.missing_msg.0.clone();
-                        let check_channel = self.missing_check_msg.0.clone();
-                        let collection = self.collection.clone().unwrap();
-                        let decklist = self.decklist.clone().unwrap();
-                        let database = self.dc.database_cards.clone();
-                        self.missing_counter += 1;
-                        thread::spawn(move || {
-                            let missing_cards =
-                                task::block_on(find_missing_cards(collection, decklist));
-                            let mut checks = Vec::new();
-                            if missing_cards.is_some() {
-                                for card in missing_cards.as_ref().unwrap() {
-                                    let missing_str = if !database.is_empty() {
-                                        check_missing(&database, card)
-                                    } else {
-                                        "".to_string()
-                                    };
-                                    checks.push(format!("{}{}", card, missing_str));
-                                }
-                            }
-                            if let Ok(()) = check_channel.send(checks) {};
-                            if let Ok(()) = missing_channel.send(missing_cards) {};
-                        });
-                        self.waiting_for_missing = true;
-                    }
+                    self.start_missing();
                     self.loading_decklist = false;
                     self.redraw = true;
                 }
@@ -587,21 +809,168 @@ impl App {
             {
                 let decklist = self.decklist.clone().unwrap();
                 let database = self.dc.database_cards.clone();
+                let game = self.collection_source.game();
                 let legal_msg = self.legal_msg.0.clone();
+                let commander_msg = self.commander_msg.0.clone();
                 self.legal_counter += 1;
                 thread::spawn(move || {
-                    let legal = task::block_on(check_legality(&decklist, &database));
+                    let legal = task::block_on(check_legality(&decklist, &database, game));
+                    let commander = check_commander(&decklist, &database);
+                    if let Ok(()) = commander_msg.send(commander) {};
                     if let Ok(()) = legal_msg.send(legal) {};
                 });
                 self.legal_started = true;
             }
+            if !self.dc.database_cards.is_empty() && self.decklist.is_some() && !self.stats_started
+            {
+                let decklist = self.decklist.clone().unwrap();
+                let database = self.dc.database_cards.clone();
+                let stats_msg = self.stats_msg.0.clone();
+                self.stats_counter += 1;
+                thread::spawn(move || {
+                    let stats = task::block_on(deck_stats(&decklist, &database));
+                    if let Ok(()) = stats_msg.send(stats) {};
+                });
+                self.stats_started = true;
+            }
+            if self.deck_diff.is_none() {
+                if let (Some(base), Some(decklist)) = (&self.diff_base, &self.decklist) {
+                    self.deck_diff = Some(deck_diff(
+                        base,
+                        decklist,
+                        self.collection.as_deref().unwrap_or_default(),
+                        &self.dc.database_cards,
+                        &self.currency(),
+                    ));
+                    self.redraw = true;
+                }
+            }
+            if self.active_tab == MenuTabs::Missing && !self.dc.database_cards.is_empty() {
+                self.update_substitutes();
+            }
+            if self.stats_started && !self.stats_done {
+                if let Ok(stats) = self.stats_msg.1.try_recv() {
+                    self.deck_stats = Some(stats);
+                    self.stats_done = true;
+                    self.redraw = true;
+                }
+            }
             if self.legal_started && !self.legal_done {
                 if let Ok(legal_msg) = self.legal_msg.1.try_recv() {
                     self.legality = Some(legal_msg);
+                    if let Ok(commander) = self.commander_msg.1.try_recv() {
+                        self.commander_check = commander;
+                    }
                     self.legal_done = true;
                 }
                 self.redraw = true;
             }
+            if !self.dc.database_cards.is_empty()
+                && self.collection_printings.is_some()
+                && !self.loading_collection
+                && !self.value_started
+            {
+                let printings = self.collection_printings.clone().unwrap();
+                let database = self.dc.database_cards.clone();
+                let sets = self.dc.sets.clone();
+                let history = self.dc.price_history.clone();
+                let currency = self.currency();
+                let value_msg = self.value_msg.0.clone();
+                self.value_counter += 1;
+                thread::spawn(move || {
+                    let value = task::block_on(collection_value(
+                        &printings, &database, &sets, &history, currency,
+                    ));
+                    if let Ok(()) = value_msg.send(value) {};
+                });
+                self.value_started = true;
+            }
+            if self.value_started && !self.value_done {
+                if let Ok(value) = self.value_msg.1.try_recv() {
+                    self.collection_value = Some(value);
+                    self.value_done = true;
+                    self.redraw = true;
+                }
+            }
+            if !self.dc.database_cards.is_empty()
+                && self.collection_printings.is_some()
+                && !self.loading_collection
+                && !self.loading_decklist
+                && !self.trade_started
+            {
+                let printings = self.collection_printings.clone().unwrap();
+                let database = self.dc.database_cards.clone();
+                let sets = self.dc.sets.clone();
+                let currency = self.currency();
+                // the loaded decklist is counted once, even when it's also a saved deck
+                let saved_decks: Vec<Box<Path>> = self
+                    .config
+                    .saved_decks
+                    .iter()
+                    .filter(|p| self.decklist.is_none() || self.decklist_path.as_deref() != Some(p))
+                    .cloned()
+                    .collect();
+                let decklist = self.decklist.clone();
+                let singleton = self.config.trade_singleton;
+                let min_value = self.config.trade_min_value;
+                let trade_msg = self.trade_msg.0.clone();
+                self.trade_counter += 1;
+                thread::spawn(move || {
+                    let mut decks: Vec<Vec<CollectionCard>> = saved_decks
+                        .iter()
+                        .filter_map(|p| read_decklist(p.to_string_lossy().to_string()).ok())
+                        .collect();
+                    if let Some(d) = decklist {
+                        decks.push(d);
+                    }
+                    let trade_cards = task::block_on(find_surplus(
+                        &printings, &decks, &database, &sets, currency, singleton, min_value,
+                    ));
+                    if let Ok(()) = trade_msg.send(trade_cards) {};
+                });
+                self.trade_started = true;
+            }
+            if self.trade_started && !self.trade_done {
+                if let Ok(trade_cards) = self.trade_msg.1.try_recv() {
+                    self.trade_cards = Some(trade_cards);
+                    self.trade_done = true;
+                    self.redraw = true;
+                }
+            }
+            if !self.dc.sets.is_empty()
+                && self.collection_printings.is_some()
+                && !self.loading_collection
+                && !self.sets_started
+            {
+                let printings = self.collection_printings.clone().unwrap();
+                let sets = self.dc.sets.clone();
+                let filter = self.rarity_filter;
+                let sets_msg = self.sets_msg.0.clone();
+                self.sets_counter += 1;
+                thread::spawn(move || {
+                    let completion = task::block_on(set_completion(&printings, &sets, filter));
+                    if let Ok(()) = sets_msg.send(completion) {};
+                });
+                self.sets_started = true;
+            }
+            if self.sets_started && !self.sets_done {
+                if let Ok(completion) = self.sets_msg.1.try_recv() {
+                    self.set_completion = Some(completion);
+                    self.sets_selected = 0;
+                    self.sets_scroll = 0;
+                    self.sets_done = true;
+                    self.redraw = true;
+                }
+            }
+            if self.search_started && !self.search_done {
+                if let Ok(results) = self.search_msg.1.try_recv() {
+                    self.search_status = format!("{} cards found", results.len());
+                    self.search_results = Some(results);
+                    self.search_scroll = 0;
+                    self.search_done = true;
+                    self.redraw = true;
+                }
+            }
             if !self.waiting_for_price
                 && !self.dc.database_cards.is_empty()
                 && self.missing_cards.is_some()
@@ -612,7 +981,8 @@ impl App {
                 self.waiting_for_price = true;
                 let price_channel = self.missing_scryfall_msg.0.clone();
                 let database = self.dc.database_cards.clone();
-                let currency = self.config.currency.clone();
+                let history = self.dc.price_history.clone();
+                let currency = self.currency();
                 let missing_cards = self.missing_cards.clone().unwrap();
                 let mut missing_scryfall = Vec::new();
                 self.price_counter += 1;
@@ -622,8 +992,9 @@ impl App {
                         let (price_str, price) =
                             if let Some(scryfall_match) = match_card(&card.name, &database) {
                                 let price = get_min_price(&[scryfall_match], currency.clone());
+                                let trend = trend_fmt(history.trend(&card.name, &currency));
                                 (
-                                    min_price_fmt(price, card.quantity, currency.clone()),
+                                    min_price_fmt(price, card.quantity, currency.clone()) + &trend,
                                     price * card.quantity as f64,
                                 )
                             } else {
@@ -635,15 +1006,40 @@ impl App {
                     if let Ok(()) = price_channel.send((missing_scryfall, missing_price)) {};
                 });
             }
+            if self.deck_changed && !self.waiting_for_missing && !self.waiting_for_price {
+                self.deck_changed = false;
+                self.missing_cards = None;
+                self.missing_lines.clear();
+                self.missing_price = None;
+                self.missing_price_num = None;
+                self.price_done = false;
+                self.legal_started = false;
+                self.legal_done = false;
+                self.stats_started = false;
+                self.stats_done = false;
+                self.trade_started = false;
+                self.trade_done = false;
+                self.deck_diff = None;
+                self.substitutes = None;
+                self.substitutes_counter += 1;
+                self.start_missing();
+                self.redraw = true;
+            }
             if self.waiting_for_missing {
                 if let Ok(missing_cards) = self.missing_msg.1.try_recv() {
                     self.debug_string += "received missing message\n";
                     self.missing_cards = missing_cards;
+                    self.missing_scroll = 0;
+                    self.missing_scroll_state = self.missing_scroll_state.position(0);
+                    if self.list_query_tab == MenuTabs::Missing {
+                        self.list_matches.clear();
+                    }
                 }
                 if let Ok(missing_text) = self.missing_check_msg.1.try_recv() {
                     self.debug_string += "received missing text message\n";
                     self.missing_lines = missing_text;
                     self.waiting_for_missing = false;
+                    sort_missing(self);
                 }
             }
             if self.waiting_for_price {
@@ -653,6 +1049,7 @@ impl App {
                     self.missing_price_num = Some(price);
                     self.waiting_for_price = false;
                     self.price_done = true;
+                    sort_missing(self);
                     self.redraw = true;
                 }
             }
@@ -667,6 +1064,109 @@ impl App {
         Ok(())
     }
 
+    /// finds substitutes for the highlighted missing card in the background when it or the
+    /// target format changes, one card at a time
+    fn update_substitutes(&mut self) {
+        if self.substitutes_started && !self.substitutes_done {
+            match self.substitutes_msg.1.try_recv() {
+                Ok(msg) => {
+                    if msg.counter == self.substitutes_counter {
+                        self.substitutes = Some((msg.name, msg.format, msg.substitutes));
+                        self.redraw = true;
+                    }
+                    self.substitutes_done = true;
+                }
+                Err(_) => return,
+            }
+        }
+        let card = match self
+            .missing_cards
+            .as_ref()
+            .and_then(|cards| cards.get(self.missing_scroll))
+        {
+            Some(c) => c.clone(),
+            None => return,
+        };
+        let format = target_format(self.config.target_format.as_deref(), self.legality.as_ref());
+        if let Some((name, cached_format, _)) = &self.substitutes {
+            if *name == card.name && *cached_format == format {
+                return;
+            }
+        }
+        let database = self.dc.database_cards.clone();
+        let collection = self.collection.clone().unwrap_or_default();
+        let currency = self.currency();
+        let game = self.collection_source.game();
+        let counter = self.substitutes_counter;
+        let substitutes_msg = self.substitutes_msg.0.clone();
+        thread::spawn(move || {
+            let substitutes = find_substitutes(
+                &card,
+                &database,
+                &collection,
+                &currency,
+                format.as_deref(),
+                game,
+            );
+            let msg = SubstitutesMessage {
+                counter,
+                name: card.name,
+                format,
+                substitutes,
+            };
+            if let Ok(()) = substitutes_msg.send(msg) {};
+        });
+        self.substitutes_started = true;
+        self.substitutes_done = false;
+    }
+
+    /// the keys that do an action, leaving out keys another binding took over
+    pub fn bound_keys(&self, action: Action) -> Vec<String> {
+        self.config
+            .keybindings
+            .keys(action)
+            .into_iter()
+            .filter(|k| parse_key(k).is_ok_and(|code| self.key_map.get(&code) == Some(&action)))
+            .collect()
+    }
+
+    /// prices are in Tix while an MTGO collection is loaded, otherwise in the config currency
+    pub fn currency(&self) -> PriceType {
+        self.collection_source.currency(&self.config.currency)
+    }
+
+    /// compares the decklist to the collection in the background, if both are loaded
+    fn start_missing(&mut self) {
+        if self.collection.is_none() || self.decklist.is_none() || self.waiting_for_missing {
+            return;
+        }
+        self.debug_string += "starting missing cards thread...\n";
+        let missing_channel = self.missing_msg.0.clone();
+        let check_channel = self.missing_check_msg.0.clone();
+        let collection = self.collection.clone().unwrap();
+        let decklist = self.decklist.clone().unwrap();
+        let database = self.dc.database_cards.clone();
+        let source = self.collection_source;
+        self.missing_counter += 1;
+        thread::spawn(move || {
+            let missing_cards = task::block_on(find_missing_cards(collection, decklist));
+            let mut checks = Vec::new();
+            if missing_cards.is_some() {
+                for card in missing_cards.as_ref().unwrap() {
+                    let missing_str = if !database.is_empty() {
+                        check_missing(&database, card, source)
+                    } else {
+                        "".to_string()
+                    };
+                    checks.push(format!("{}{}", card, missing_str));
+                }
+            }
+            if let Ok(()) = check_channel.send(checks) {};
+            if let Ok(()) = missing_channel.send(missing_cards) {};
+        });
+        self.waiting_for_missing = true;
+    }
+
     /// render the frame
     fn render_frame(
         &mut self,
@@ -687,11 +1187,19 @@ impl App {
     ) -> io::Result<()> {
         if event::poll(Duration::from_millis(100))? {
             let event = event::read()?;
-            self.redraw = true;
+            // mouse movement alone doesn't change anything
+            self.redraw = !matches!(
+                event,
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Moved,
+                    ..
+                })
+            );
             match event {
                 Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                     self.handle_key_event(key_event);
                 }
+                Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
                 _ => {}
             };
             if self.active_tab == MenuTabs::Collection && self.collection.is_none() {
@@ -707,26 +1215,97 @@ impl App {
         Ok(())
     }
 
+    /// mouse clicks on the tab bar and list rows, and wheel scrolling
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        // the mouse is ignored while typing
+        if self.search_editing || self.list_query_editing || self.deck_input_mode.is_some() {
+            return;
+        }
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if self.tabs_area.contains(position) {
+                    if let Some(tab) = tab_at(self.tabs_area, mouse_event.column) {
+                        self.active_tab = tab;
+                    }
+                } else if self.list_area.contains(position) {
+                    click_row(self, (mouse_event.row - self.list_area.y) as usize);
+                }
+            }
+            MouseEventKind::ScrollDown => {
+                for _ in 0..SCROLL_ROWS {
+                    down_press(self);
+                }
+            }
+            MouseEventKind::ScrollUp => {
+                for _ in 0..SCROLL_ROWS {
+                    up_press(self);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// key events
     fn handle_key_event(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            // TODO: delete after app framework is complete
-            KeyCode::Char('1') => self.active_tab = MenuTabs::Welcome,
-            KeyCode::Char('2') => self.active_tab = MenuTabs::Database,
-            KeyCode::Char('3') => self.active_tab = MenuTabs::Collection,
-            KeyCode::Char('4') => self.active_tab = MenuTabs::Deck,
-            KeyCode::Char('5') => self.active_tab = MenuTabs::Missing,
-            KeyCode::Char('6') => self.active_tab = MenuTabs::Help,
-            KeyCode::Char('0') => self.active_tab = MenuTabs::Debug,
-            KeyCode::Char('q') => self.exit(),
-            KeyCode::Char('c') => c_press(self),
-            KeyCode::Char('s') => s_press(self),
-            KeyCode::Char('f') => f_press(self),
-            KeyCode::Enter => enter_press(self),
-            KeyCode::Up => up_press(self),
-            KeyCode::Down => down_press(self),
-            KeyCode::Esc => esc_press(self),
-            _ => {}
+        if self.search_editing {
+            search_key(self, key_event);
+            return;
+        }
+        if self.list_query_editing {
+            list_query_key(self, key_event);
+            return;
+        }
+        if self.deck_editing && self.active_tab == MenuTabs::Deck && deck_edit_key(self, key_event)
+        {
+            return;
+        }
+        let action = match self.key_map.get(&key_event.code) {
+            Some(a) => *a,
+            None => return,
+        };
+        match action {
+            Action::TabWelcome => self.active_tab = MenuTabs::Welcome,
+            Action::TabDatabase => self.active_tab = MenuTabs::Database,
+            Action::TabCollection => self.active_tab = MenuTabs::Collection,
+            Action::TabDeck => self.active_tab = MenuTabs::Deck,
+            Action::TabMissing => self.active_tab = MenuTabs::Missing,
+            Action::TabHelp => self.active_tab = MenuTabs::Help,
+            Action::TabValue => self.active_tab = MenuTabs::Value,
+            Action::TabSets => self.active_tab = MenuTabs::Sets,
+            Action::TabSearch => self.active_tab = MenuTabs::Search,
+            Action::TabDebug => self.active_tab = MenuTabs::Debug,
+            Action::Quit => self.exit(),
+            Action::Copy => c_press(self),
+            Action::Load => s_press(self),
+            Action::SaveFile => f_press(self),
+            Action::Toggle => t_press(self),
+            Action::Rarity => r_press(self),
+            Action::ListSearch => slash_press(self),
+            Action::Sort => o_press(self),
+            Action::SampleHand => h_press(self),
+            Action::Compare => d_press(self),
+            Action::Edit => e_press(self),
+            Action::Report => p_press(self),
+            Action::Currency => switch_currency(self),
+            Action::PriceColumns => {
+                if self.active_tab == MenuTabs::Missing {
+                    self.config.price_columns = !self.config.price_columns;
+                }
+            }
+            Action::Wildcards => w_press(self),
+            Action::NextMatch => n_press(self, true),
+            Action::PreviousMatch => n_press(self, false),
+            Action::Select => enter_press(self),
+            Action::Up => up_press(self),
+            Action::Down => down_press(self),
+            Action::Left => left_press(self),
+            Action::Right => right_press(self),
+            Action::PageUp => scroll_by(self, page_rows(self), false),
+            Action::PageDown => scroll_by(self, page_rows(self), true),
+            Action::Home => scroll_by(self, scroll_len(self), false),
+            Action::End => scroll_by(self, scroll_len(self), true),
+            Action::Back => esc_press(self),
         }
     }
 
@@ -773,10 +1352,238 @@ fn enter_press(app: &mut App) {
                 }
             }
         }
+        MenuTabs::Search => app.search_editing = true,
         _ => {}
     }
 }
 
+/// typing in the search box on the search tab
+fn search_key(app: &mut App, key_event: KeyEvent) {
+    match app.search_input.handle_key(key_event.code) {
+        InputAction::Edited => {
+            app.search_input.update(&app.card_names);
+        }
+        InputAction::Cancelled => {
+            app.search_editing = false;
+            app.search_input.suggestions.clear();
+        }
+        InputAction::Submitted => {
+            app.search_input.suggestions.clear();
+            app.search_editing = false;
+            start_search(app);
+        }
+        _ => {}
+    }
+}
+
+/// starts typing a search for the list on the current tab
+fn slash_press(app: &mut App) {
+    if current_list(app).is_some() {
+        app.list_query_editing = true;
+        app.list_query_tab = app.active_tab;
+        app.list_query_input.clear();
+        app.list_query_status.clear();
+        app.list_matches.clear();
+    }
+}
+
+/// typing after / on a list, jumping to the first match as you type
+/// suggests names from the list being searched
+fn list_query_key(app: &mut App, key_event: KeyEvent) {
+    match app.list_query_input.handle_key(key_event.code) {
+        InputAction::Edited => {
+            let names: Vec<String> = current_list(app)
+                .map(|cards| cards.iter().map(|c| c.name.clone()).collect())
+                .unwrap_or_default();
+            app.list_query_input
+                .update(&NameIndex::new(names.iter().map(|n| n.as_str())));
+            update_list_matches(app);
+        }
+        InputAction::Completed => update_list_matches(app),
+        InputAction::Submitted => {
+            app.list_query_editing = false;
+            app.list_query_input.suggestions.clear();
+        }
+        InputAction::Cancelled => {
+            app.list_query_editing = false;
+            app.list_query_input.clear();
+            app.list_matches.clear();
+        }
+        _ => {}
+    }
+}
+
+/// the cards shown on the current tab, if it has a list
+fn current_list(app: &App) -> Option<&Vec<CollectionCard>> {
+    match app.active_tab {
+        MenuTabs::Collection => app.collection.as_ref(),
+        MenuTabs::Deck => app.decklist.as_ref(),
+        MenuTabs::Missing => app.missing_cards.as_ref(),
+        _ => None,
+    }
+}
+
+/// the highlighted row of the list on the current tab
+fn selected_row(app: &App) -> usize {
+    match app.active_tab {
+        MenuTabs::Collection => app.collection_scroll,
+        MenuTabs::Deck => app.decklist_scroll,
+        MenuTabs::Missing => app.missing_scroll,
+        _ => 0,
+    }
+}
+
+/// moves the selection on the current tab by a number of rows
+fn scroll_by(app: &mut App, rows: usize, down: bool) {
+    for _ in 0..rows {
+        if down {
+            down_press(app);
+        } else {
+            up_press(app);
+        }
+    }
+}
+
+/// rows in a page of the list on the current tab
+fn page_rows(app: &App) -> usize {
+    match app.list_area.height {
+        0 => PAGE_ROWS,
+        height => height as usize,
+    }
+}
+
+/// rows that can be scrolled through on the current tab
+fn scroll_len(app: &App) -> usize {
+    match app.active_tab {
+        MenuTabs::Value if app.trade_view => app.trade_cards.as_ref().map_or(0, |t| t.len()),
+        MenuTabs::Value => app.collection_value.as_ref().map_or(0, |v| v.by_set.len()),
+        MenuTabs::Sets => app.set_completion.as_ref().map_or(0, |s| s.len()),
+        MenuTabs::Search => app.search_results.as_ref().map_or(0, |r| r.len()),
+        _ => current_list(app).map_or(0, |cards| cards.len()),
+    }
+}
+
+/// selects the row at a line of the list on the current tab, counting the rows scrolled past
+fn click_row(app: &mut App, line: usize) {
+    let offset = match app.active_tab {
+        MenuTabs::Collection => app.collection_list_state.offset(),
+        MenuTabs::Deck => app.decklist_list_state.offset(),
+        MenuTabs::Missing => app.missing_list_state.offset(),
+        _ => return,
+    };
+    let row = offset + line;
+    if current_list(app).is_some_and(|cards| row < cards.len()) {
+        select_row(app, row);
+    }
+}
+
+/// highlights a row of the list on the current tab
+fn select_row(app: &mut App, row: usize) {
+    match app.active_tab {
+        MenuTabs::Collection => {
+            app.collection_scroll = row;
+            app.collection_scroll_state = app.collection_scroll_state.position(row);
+        }
+        MenuTabs::Deck => {
+            app.decklist_scroll = row;
+            app.decklist_scroll_state = app.decklist_scroll_state.position(row);
+        }
+        MenuTabs::Missing => {
+            app.missing_scroll = row;
+            app.missing_scroll_state = app.missing_scroll_state.position(row);
+        }
+        _ => {}
+    }
+}
+
+/// finds every card matching the list query and jumps to the first one at or below the
+/// highlighted row
+fn update_list_matches(app: &mut App) {
+    let query = match app.list_query_input.text.parse::<ListQuery>() {
+        Ok(q) => q,
+        Err(e) => {
+            app.list_query_status = format!("{}  ({})", e, FILTER_HELP);
+            app.list_matches.clear();
+            return;
+        }
+    };
+    app.list_matches = match current_list(app) {
+        Some(cards) => find_matches(cards, &query, &app.dc.database_cards, &app.currency()),
+        None => Vec::new(),
+    };
+    let selected = selected_row(app);
+    let first = app
+        .list_matches
+        .iter()
+        .find(|i| **i >= selected)
+        .or(app.list_matches.first())
+        .copied();
+    if let Some(row) = first {
+        select_row(app, row);
+    }
+    app.list_query_status = format!("{} matches", app.list_matches.len());
+}
+
+/// steps to the next (n) or previous (N) card matching the list query, wrapping around
+fn n_press(app: &mut App, forward: bool) {
+    if app.list_query_tab != app.active_tab || app.list_matches.is_empty() {
+        return;
+    }
+    let selected = selected_row(app);
+    let row = if forward {
+        app.list_matches
+            .iter()
+            .find(|i| **i > selected)
+            .or(app.list_matches.first())
+    } else {
+        app.list_matches
+            .iter()
+            .rev()
+            .find(|i| **i < selected)
+            .or(app.list_matches.last())
+    };
+    if let Some(row) = row.copied() {
+        select_row(app, row);
+    }
+}
+
+/// parses the search box and searches the database in a separate thread
+fn start_search(app: &mut App) {
+    if app.dc.database_cards.is_empty() {
+        app.search_status = "Searching needs the card database.".to_string();
+        return;
+    }
+    if app.search_started && !app.search_done {
+        return;
+    }
+    let query = match app.search_input.text.parse::<SearchQuery>() {
+        Ok(q) => q,
+        Err(e) => {
+            app.search_status = format!("{}  ({})", e, SEARCH_HELP);
+            return;
+        }
+    };
+    let database = app.dc.database_cards.clone();
+    let sets = app.dc.sets.clone();
+    let collection = app.collection.clone().unwrap_or_default();
+    let currency = app.config.currency.clone();
+    let search_msg = app.search_msg.0.clone();
+    app.search_counter += 1;
+    app.search_status = "Searching...".to_string();
+    thread::spawn(move || {
+        let results = task::block_on(search_database(
+            &query,
+            &database,
+            &sets,
+            &collection,
+            currency,
+        ));
+        if let Ok(()) = search_msg.send(results) {};
+    });
+    app.search_started = true;
+    app.search_done = false;
+}
+
 fn c_press(app: &mut App) {
     match app.active_tab {
         MenuTabs::Welcome => {
@@ -829,10 +1636,12 @@ fn c_press(app: &mut App) {
         }
         MenuTabs::Missing => {
             if app.missing_cards.is_some() {
-                let mut clipboard_string = String::new();
-                for card in app.missing_cards.as_ref().unwrap().iter() {
-                    clipboard_string += &format!("{}\n", card);
-                }
+                let clipboard_string = missing_list_string(
+                    app.missing_cards.as_ref().unwrap(),
+                    &app.dc.database_cards,
+                    app.config.missing_format,
+                    app.config.missing_set_codes,
+                );
                 app.debug_string += &clipboard_string;
                 if app.clipboard.is_ok() {
                     let clipboard = app.clipboard.as_mut().unwrap();
@@ -868,6 +1677,14 @@ fn s_press(app: &mut App) {
                         app.load_started = false;
                         app.load_done = false;
                         app.price_done = false;
+                        app.value_started = false;
+                        app.value_done = false;
+                        app.trade_started = false;
+                        app.trade_done = false;
+                        app.sets_started = false;
+                        app.sets_done = false;
+                        app.stats_started = false;
+                        app.stats_done = false;
                     }
                 }
             }
@@ -881,14 +1698,27 @@ fn s_press(app: &mut App) {
                     app.loading_collection = true;
                     app.missing_cards = None;
                     app.legality = None;
+                    app.commander_check = None;
+                    app.collection_value = None;
+                    app.value_started = false;
+                    app.value_done = false;
+                    app.trade_cards = None;
+                    app.trade_started = false;
+                    app.trade_done = false;
+                    app.set_completion = None;
+                    app.sets_started = false;
+                    app.sets_done = false;
                     thread::spawn(move || {
                         let read_result =
-                            task::block_on(read_moxfield_collection(path_string.clone()));
+                            task::block_on(read_collection_printings(path_string.clone()));
                         let mut message = CollectionMessage::default();
                         match read_result {
-                            Ok(collection) => {
-                                message.debug += "read csv successfully\n\n";
-                                message.collection = Some(collection);
+                            Ok((printings, source)) => {
+                                message.debug +=
+                                    &format!("read {} collection successfully\n\n", source.label());
+                                message.collection = Some(squash_collection(printings.clone()));
+                                message.printings = Some(printings);
+                                message.source = source;
                                 message.status =
                                     format!("Collection loaded successfully: {}", path_string);
                                 message.exist = true;
@@ -918,6 +1748,20 @@ fn s_press(app: &mut App) {
                     app.loading_decklist = true;
                     app.missing_cards = None;
                     app.legality = None;
+                    app.commander_check = None;
+                    app.trade_started = false;
+                    app.trade_done = false;
+                    app.deck_stats = None;
+                    app.stats_started = false;
+                    app.stats_done = false;
+                    app.sample_hand = None;
+                    app.deck_diff = None;
+                    app.substitutes = None;
+                    app.substitutes_counter += 1;
+                    app.decklist_path = Some(PathBuf::from(&path_string));
+                    app.deck_editing = false;
+                    app.deck_unsaved = false;
+                    app.deck_changed = false;
                     thread::spawn(move || {
                         let read_result = read_decklist(path_string.clone());
                         let mut message = DecklistMessage::default();
@@ -964,6 +1808,18 @@ fn up_press(app: &mut App) {
                 app.missing_scroll_state = app.missing_scroll_state.position(app.missing_scroll);
             }
         }
+        MenuTabs::Value => {
+            if app.trade_view {
+                app.trade_scroll = app.trade_scroll.saturating_sub(1);
+            } else {
+                app.value_scroll = app.value_scroll.saturating_sub(1);
+            }
+        }
+        MenuTabs::Sets => {
+            app.sets_selected = app.sets_selected.saturating_sub(1);
+            app.sets_scroll = 0;
+        }
+        MenuTabs::Search => app.search_scroll = app.search_scroll.saturating_sub(1),
         _ => {}
     }
 }
@@ -972,7 +1828,7 @@ fn down_press(app: &mut App) {
     match app.active_tab {
         MenuTabs::Collection => {
             if app.collection.is_some()
-                && app.collection_scroll < app.collection.as_ref().unwrap().len()
+                && app.collection_scroll + 1 < app.collection.as_ref().unwrap().len()
             {
                 app.collection_scroll += 1;
                 app.collection_scroll_state =
@@ -980,7 +1836,8 @@ fn down_press(app: &mut App) {
             }
         }
         MenuTabs::Deck => {
-            if app.decklist.is_some() && app.decklist_scroll < app.decklist.as_ref().unwrap().len()
+            if app.decklist.is_some()
+                && app.decklist_scroll + 1 < app.decklist.as_ref().unwrap().len()
             {
                 app.decklist_scroll += 1;
                 app.decklist_scroll_state = app.decklist_scroll_state.position(app.decklist_scroll);
@@ -988,26 +1845,92 @@ fn down_press(app: &mut App) {
         }
         MenuTabs::Missing => {
             if app.missing_cards.is_some()
-                && app.missing_scroll < app.missing_cards.as_ref().unwrap().len()
+                && app.missing_scroll + 1 < app.missing_cards.as_ref().unwrap().len()
             {
                 app.missing_scroll += 1;
                 app.missing_scroll_state = app.missing_scroll_state.position(app.missing_scroll);
             }
         }
+        MenuTabs::Value => {
+            if app.trade_view {
+                if let Some(trade_cards) = &app.trade_cards {
+                    if app.trade_scroll + 1 < trade_cards.len() {
+                        app.trade_scroll += 1;
+                    }
+                }
+            } else if let Some(value) = &app.collection_value {
+                if app.value_scroll + 1 < value.by_set.len() {
+                    app.value_scroll += 1;
+                }
+            }
+        }
+        MenuTabs::Sets => {
+            if let Some(completion) = &app.set_completion {
+                if app.sets_selected + 1 < completion.len() {
+                    app.sets_selected += 1;
+                    app.sets_scroll = 0;
+                }
+            }
+        }
+        MenuTabs::Search => {
+            if let Some(results) = &app.search_results {
+                if app.search_scroll + 1 < results.len() {
+                    app.search_scroll += 1;
+                }
+            }
+        }
         _ => {}
     }
 }
 
+/// scrolls the missing printings of the selected set
+fn left_press(app: &mut App) {
+    if app.active_tab == MenuTabs::Sets {
+        app.sets_scroll = app.sets_scroll.saturating_sub(1);
+    }
+}
+
+fn right_press(app: &mut App) {
+    if app.active_tab == MenuTabs::Sets {
+        if let Some(set) = app
+            .set_completion
+            .as_ref()
+            .and_then(|c| c.get(app.sets_selected))
+        {
+            if app.sets_scroll + 1 < set.missing.len() {
+                app.sets_scroll += 1;
+            }
+        }
+    }
+}
+
 fn esc_press(app: &mut App) {
+    // clear an active list search before resetting the file
+    if app.list_query_tab == app.active_tab && !app.list_query_input.is_empty() {
+        app.list_query_input.clear();
+        app.list_matches.clear();
+        return;
+    }
     match app.active_tab {
         MenuTabs::Collection => {
             app.collection = None;
+            app.collection_printings = None;
+            app.collection_value = None;
+            app.value_started = false;
+            app.value_done = false;
+            app.trade_cards = None;
+            app.trade_started = false;
+            app.trade_done = false;
+            app.set_completion = None;
+            app.sets_started = false;
+            app.sets_done = false;
             // NOTE: prevents autoloading when manually selecting a file
             app.config.collection_path = None;
             app.collection_exist = false;
         }
         MenuTabs::Deck => {
             app.decklist = None;
+            app.deck_editing = false;
         }
         _ => {}
     }
@@ -1020,16 +1943,19 @@ fn f_press(app: &mut App) {
                 && app.decklist_file.is_some()
                 && app.decklist_file_name.is_some()
             {
-                let mut file_string = String::new();
-                for card in app.missing_cards.as_ref().unwrap().iter() {
-                    file_string += &format!("{}\n", card);
-                }
+                let file_string = missing_list_string(
+                    app.missing_cards.as_ref().unwrap(),
+                    &app.dc.database_cards,
+                    app.config.missing_format,
+                    app.config.missing_set_codes,
+                );
                 if let Some(missing_directory) = app.decklist_file.as_ref().unwrap().path().parent()
                 {
-                    let missing_filename = missing_directory.to_path_buf().join(format!(
-                        "missing_{}",
-                        app.decklist_file_name.as_ref().unwrap()
-                    ));
+                    let missing_filename = missing_directory.to_path_buf().join(
+                        app.config
+                            .missing_format
+                            .file_name(app.decklist_file_name.as_ref().unwrap()),
+                    );
                     app.debug_string += &format!("missing filename: {:?}\n", missing_filename);
                     match fs::write(missing_filename, file_string) {
                         Ok(()) => {
@@ -1043,6 +1969,347 @@ fn f_press(app: &mut App) {
                 }
             }
         }
+        MenuTabs::Value if app.trade_view => {
+            if let (Some(trade_cards), Some(collection_path)) =
+                (&app.trade_cards, &app.collection_path)
+            {
+                let trade_path = PathBuf::from(collection_path)
+                    .with_file_name(app.config.trade_format.file_name());
+                let trade_string = trade_list_string(trade_cards, &app.config.trade_format);
+                match fs::write(&trade_path, trade_string) {
+                    Ok(()) => app.value_status = format!("Trade list saved to {:?}", trade_path),
+                    Err(e) => app.value_status = format!("Failed to save trade list: {}", e),
+                }
+            }
+        }
+        MenuTabs::Value => {
+            if let (Some(value), Some(collection_path)) =
+                (&app.collection_value, &app.collection_path)
+            {
+                let value_path =
+                    PathBuf::from(collection_path).with_file_name("collection_value.csv");
+                match write_value_csv(value, &value_path) {
+                    Ok(()) => app.value_status = format!("Report saved to {:?}", value_path),
+                    Err(e) => app.value_status = format!("Failed to save report: {}", e),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// on the Deck tab, keeps the loaded deck as the old version and goes back to the file explorer
+/// to pick the new version, pressing it again stops comparing
+fn d_press(app: &mut App) {
+    if app.active_tab != MenuTabs::Deck || app.loading_decklist {
+        return;
+    }
+    if app.diff_base.is_some() {
+        app.diff_base = None;
+        app.diff_base_name.clear();
+        app.deck_diff = None;
+    } else if let Some(decklist) = app.decklist.take() {
+        app.diff_base = Some(decklist);
+        app.diff_base_name = app.decklist_file_name.clone().unwrap_or_default();
+        app.deck_diff = None;
+    }
+}
+
+/// starts editing the loaded decklist
+fn e_press(app: &mut App) {
+    if app.active_tab == MenuTabs::Deck && app.decklist.is_some() && !app.loading_decklist {
+        app.deck_editing = true;
+        app.deck_edit_status.clear();
+    }
+}
+
+/// keys for the decklist editor, returns false for keys it doesn't use
+fn deck_edit_key(app: &mut App, key_event: KeyEvent) -> bool {
+    let selected = app.decklist_scroll;
+    if app.deck_input_mode == Some(DeckInputMode::SaveAs) {
+        save_as_key(app, key_event);
+        return true;
+    }
+    if let Some(mode) = app.deck_input_mode {
+        match app.deck_name_input.handle_key(key_event.code) {
+            InputAction::Edited => {
+                app.deck_name_input.update(&app.card_names);
+            }
+            InputAction::Submitted => {
+                let name = match app.deck_name_input.selected_name() {
+                    Some(n) => n.to_string(),
+                    None => app.deck_name_input.text.trim().to_string(),
+                };
+                if !name.is_empty() {
+                    let not_found = !app.dc.database_cards.is_empty()
+                        && !app
+                            .dc
+                            .database_cards
+                            .contains_key(&make_safe_name(&name, true));
+                    let status = match mode {
+                        DeckInputMode::Add => {
+                            edit_decklist(app, |d| add_card(d, &name, 1, DeckSection::Main));
+                            format!("Added {}.", name)
+                        }
+                        DeckInputMode::Rename => {
+                            edit_decklist(app, |d| rename_card(d, selected, &name));
+                            format!("Renamed to {}.", name)
+                        }
+                        DeckInputMode::SaveAs => return true, // handled by save_as_key
+                    };
+                    app.deck_edit_status = if not_found {
+                        format!("{}  It was not found in the database.", status)
+                    } else {
+                        status
+                    };
+                }
+                app.deck_input_mode = None;
+            }
+            InputAction::Cancelled => app.deck_input_mode = None,
+            _ => {}
+        }
+        return true;
+    }
+    match key_event.code {
+        KeyCode::Char('+') | KeyCode::Char('=') => edit_decklist(app, |d| {
+            change_quantity(d, selected, 1);
+            selected
+        }),
+        KeyCode::Char('-') => edit_decklist(app, |d| {
+            change_quantity(d, selected, -1);
+            selected
+        }),
+        KeyCode::Char('m') => edit_decklist(app, |d| move_card(d, selected)),
+        KeyCode::Char('a') => {
+            app.deck_input_mode = Some(DeckInputMode::Add);
+            app.deck_name_input.clear();
+        }
+        // starts with the current name so a misspelling can be fixed
+        KeyCode::Char('r') => {
+            if let Some(card) = app.decklist.as_ref().and_then(|d| d.get(selected)) {
+                app.deck_name_input.clear();
+                app.deck_name_input.text = card.name.clone();
+                app.deck_name_input.update(&app.card_names);
+                app.deck_input_mode = Some(DeckInputMode::Rename);
+            }
+        }
+        KeyCode::Char('w') => save_decklist(app),
+        KeyCode::Esc | KeyCode::Char('e') => {
+            app.deck_editing = false;
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// typing the file name for a decklist that doesn't have a file yet
+fn save_as_key(app: &mut App, key_event: KeyEvent) {
+    match app.deck_name_input.handle_key(key_event.code) {
+        InputAction::Submitted => {
+            app.deck_input_mode = None;
+            let path = app.deck_name_input.text.trim().to_string();
+            if path.is_empty() {
+                app.deck_edit_status = "The decklist was not saved.".to_string();
+                return;
+            }
+            let path = PathBuf::from(path);
+            app.decklist_file_name = path.file_name().map(|n| n.to_string_lossy().to_string());
+            app.decklist_path = Some(path);
+            save_decklist(app);
+        }
+        InputAction::Cancelled => {
+            app.deck_input_mode = None;
+            app.deck_edit_status = "The decklist was not saved.".to_string();
+        }
+        _ => {}
+    }
+}
+
+/// applies an edit to the loaded decklist and re-runs the checks that use it
+/// the edit returns the position of the card to select afterwards
+fn edit_decklist(app: &mut App, edit: impl FnOnce(&mut Vec<CollectionCard>) -> usize) {
+    if let Some(decklist) = app.decklist.as_mut() {
+        let selected = edit(decklist);
+        app.decklist_scroll = selected.min(decklist.len().saturating_sub(1));
+        app.decklist_scroll_state = app.decklist_scroll_state.position(app.decklist_scroll);
+        if app.list_query_tab == MenuTabs::Deck {
+            app.list_matches.clear();
+        }
+        app.deck_unsaved = true;
+        app.deck_changed = true;
+    }
+}
+
+/// writes the edited decklist back to the file it was loaded from
+/// asks for a file name first when the decklist doesn't have a file
+fn save_decklist(app: &mut App) {
+    let (path, decklist) = match (&app.decklist_path, &app.decklist) {
+        (Some(p), Some(d)) => (p, d),
+        (None, Some(_)) => {
+            app.debug_string += "Decklist has no file path, asking for one to save it to.\n";
+            app.deck_edit_status = "The decklist has no file yet, enter a file name.".to_string();
+            app.deck_name_input.clear();
+            app.deck_name_input.text = app
+                .decklist_file_name
+                .clone()
+                .unwrap_or_else(|| "decklist.txt".to_string());
+            app.deck_input_mode = Some(DeckInputMode::SaveAs);
+            return;
+        }
+        _ => {
+            app.deck_edit_status = "There is no decklist to save.".to_string();
+            return;
+        }
+    };
+    // keep the line endings, section labels and commander marker of the original file
+    let original = fs::read_to_string(path).unwrap_or_default();
+    let line_ending = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let deck_labels = read_deck_labels(&original);
+    match fs::write(path, decklist_string(decklist, &deck_labels, line_ending)) {
+        Ok(()) => {
+            app.deck_edit_status = format!("Saved {}", path.display());
+            app.deck_unsaved = false;
+        }
+        Err(e) => app.deck_edit_status = format!("Could not save the decklist: {}", e),
+    }
+}
+
+/// draws a new sample opening hand on the Deck tab stats view
+fn h_press(app: &mut App) {
+    if app.active_tab == MenuTabs::Deck && app.stats_view {
+        if let Some(decklist) = &app.decklist {
+            app.sample_seed += 1;
+            app.sample_hand = sample_hands(decklist, HAND_SIZE, 1, app.sample_seed).pop();
+        }
+    }
+}
+
+/// switches to the next currency and redoes everything that has prices
+/// the database keeps the cheapest price of every currency, so it doesn't need to be reloaded
+fn switch_currency(app: &mut App) {
+    // results still on the way would come back in the old currency, and MTGO cards only have
+    // Tix prices
+    if app.collection_source == CollectionSource::Mtgo
+        || app.waiting_for_price
+        || (app.value_started && !app.value_done)
+        || (app.trade_started && !app.trade_done)
+    {
+        return;
+    }
+    app.config.currency = app.config.currency.next();
+    app.missing_price = None;
+    app.missing_price_num = None;
+    app.price_done = false;
+    app.value_started = false;
+    app.value_done = false;
+    app.trade_started = false;
+    app.trade_done = false;
+    app.deck_diff = None;
+    app.substitutes = None;
+    app.substitutes_counter += 1;
+}
+
+/// saves the shopping report for the missing cards next to the decklist
+fn p_press(app: &mut App) {
+    if app.active_tab != MenuTabs::Missing {
+        return;
+    }
+    let (missing_cards, decklist_path) = match (&app.missing_cards, &app.decklist_path) {
+        (Some(m), Some(p)) => (m, p),
+        _ => return,
+    };
+    let deck_name = decklist_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let report = shopping_report(
+        &deck_name,
+        missing_cards,
+        &app.dc.database_cards,
+        &app.currency(),
+        app.legality.as_ref(),
+        app.config.report_format,
+    );
+    let report_path = decklist_path.with_file_name(app.config.report_format.file_name(&deck_name));
+    match fs::write(&report_path, report) {
+        Ok(()) => {
+            app.debug_string += &format!("Shopping report saved to {:?}\n", report_path);
+        }
+        Err(e) => app.debug_string += &format!("Failed to save shopping report: {}\n", e),
+    }
+}
+
+/// switches the Missing tab between prices and Arena wildcards, grouping the cards by rarity
+fn w_press(app: &mut App) {
+    if app.active_tab != MenuTabs::Missing {
+        return;
+    }
+    app.wildcard_mode = !app.wildcard_mode;
+    if app.wildcard_mode && !app.dc.database_cards.is_empty() {
+        app.missing_sort = MissingSort::Rarity;
+        sort_missing(app);
+    }
+}
+
+/// cycles the sort order of the missing cards
+fn o_press(app: &mut App) {
+    if app.active_tab == MenuTabs::Missing {
+        app.missing_sort = app.missing_sort.next(!app.dc.database_cards.is_empty());
+        sort_missing(app);
+    }
+}
+
+/// puts the missing cards, their lines and their prices in the selected order
+/// waits until the missing and price threads are done so the lists stay lined up
+fn sort_missing(app: &mut App) {
+    if app.waiting_for_missing || app.waiting_for_price {
+        return;
+    }
+    let (missing_cards, decklist) = match (&app.missing_cards, &app.decklist) {
+        (Some(m), Some(d)) => (m, d),
+        _ => return,
+    };
+    let order = missing_order(
+        missing_cards,
+        decklist,
+        app.missing_sort,
+        &app.dc.database_cards,
+        &app.currency(),
+    );
+    if let Some(cards) = app.missing_cards.as_mut() {
+        apply_order(cards, &order);
+    }
+    apply_order(&mut app.missing_lines, &order);
+    if let Some(prices) = app.missing_price.as_mut() {
+        apply_order(prices, &order);
+    }
+    if let Some(prices) = app.missing_price_num.as_mut() {
+        apply_order(prices, &order);
+    }
+    app.missing_scroll = 0;
+    app.missing_scroll_state = app.missing_scroll_state.position(0);
+    if app.list_query_tab == MenuTabs::Missing {
+        app.list_matches.clear();
+    }
+}
+
+fn r_press(app: &mut App) {
+    if app.active_tab == MenuTabs::Sets && (!app.sets_started || app.sets_done) {
+        app.rarity_filter = app.rarity_filter.next();
+        app.sets_started = false;
+        app.sets_done = false;
+    }
+}
+
+fn t_press(app: &mut App) {
+    match app.active_tab {
+        MenuTabs::Value => app.trade_view = !app.trade_view,
+        MenuTabs::Deck => app.stats_view = !app.stats_view,
+        MenuTabs::Missing => app.config.missing_format = app.config.missing_format.next(),
         _ => {}
     }
 }
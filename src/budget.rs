@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::{
+    collection::CollectionCard,
+    database::scryfall::{get_min_price, make_safe_name, PriceType, ScryfallCard},
+};
+
+/// the missing cards that fit in a budget
+#[derive(Clone, Debug, Default)]
+pub struct BudgetPlan {
+    pub budget: f64,
+    pub buy: Vec<u64>, // copies to buy of each missing card, in the same order
+    pub cost: f64,
+    pub skipped: usize, // cards left out because they aren't in the database
+}
+
+impl BudgetPlan {
+    /// copies bought across every card
+    pub fn copies(&self) -> u64 {
+        self.buy.iter().sum()
+    }
+
+    pub fn left(&self) -> f64 {
+        self.budget - self.cost
+    }
+}
+
+/// price of one copy of each missing card, None if it isn't in the database
+pub fn unit_prices(
+    missing: &[CollectionCard],
+    database: &HashMap<String, ScryfallCard>,
+    currency: &PriceType,
+) -> Vec<Option<f64>> {
+    missing
+        .iter()
+        .map(|card| {
+            database
+                .get(&make_safe_name(&card.name, true))
+                .map(|m| get_min_price(std::slice::from_ref(m), currency.clone()))
+        })
+        .collect()
+}
+
+/// the cards above the per card price threshold, a threshold of 0 turns it off
+pub fn over_threshold(unit_price: Option<f64>, threshold: f64) -> bool {
+    threshold > 0.0 && unit_price.is_some_and(|p| p > threshold)
+}
+
+/// picks the missing cards to buy first with a limited budget
+/// cards the deck plays more copies of come first, then cheaper cards, and as many copies are
+/// bought as still fit
+pub fn budget_plan(
+    missing: &[CollectionCard],
+    decklist: &[CollectionCard],
+    unit_prices: &[Option<f64>],
+    budget: f64,
+) -> BudgetPlan {
+    let mut deck_quantity: HashMap<String, u64> = HashMap::new();
+    for card in decklist {
+        *deck_quantity
+            .entry(make_safe_name(&card.name, true))
+            .or_insert(0) += card.quantity;
+    }
+    let quantity = |i: usize| {
+        *deck_quantity
+            .get(&make_safe_name(&missing[i].name, true))
+            .unwrap_or(&0)
+    };
+    let mut order: Vec<usize> = (0..missing.len()).collect();
+    order.sort_by(|a, b| {
+        quantity(*b).cmp(&quantity(*a)).then_with(|| {
+            let price = |i: usize| unit_prices.get(i).copied().flatten().unwrap_or(f64::MAX);
+            price(*a).total_cmp(&price(*b))
+        })
+    });
+    let mut plan = BudgetPlan {
+        budget,
+        buy: vec![0; missing.len()],
+        ..Default::default()
+    };
+    for i in order {
+        let unit_price = match unit_prices.get(i).copied().flatten() {
+            Some(p) => p,
+            None => {
+                plan.skipped += 1;
+                continue;
+            }
+        };
+        let copies = if unit_price > 0.0 {
+            // a little slack so rounding doesn't drop a copy that fits exactly
+            ((((budget - plan.cost) / unit_price) + 1e-9)
+                .floor()
+                .max(0.0) as u64)
+                .min(missing[i].quantity)
+        } else {
+            missing[i].quantity
+        };
+        plan.buy[i] = copies;
+        plan.cost += unit_price * copies as f64;
+    }
+    plan
+}
@@ -0,0 +1,591 @@
+use std::{collections::HashMap, error::Error, fs, path::PathBuf, str::FromStr};
+
+use async_std::task;
+use directories_next::ProjectDirs;
+
+use crate::{
+    budget::{budget_plan, over_threshold, unit_prices},
+    collection::{
+        find_missing_cards, main_deck, read_collection_printings, read_decklist, squash_collection,
+        CollectionCard, CollectionSource,
+    },
+    config::DecklistConfig,
+    database::scryfall::{make_safe_name, match_card},
+    diff::deck_diff,
+    odds::{cards_seen, prob_by_turn, sample_hands, sampled_prob_at_least, HAND_SIZE},
+    search::{search_database, SearchQuery, SEARCH_HELP},
+    startup::{
+        config_check, database_check, dl_scryfall_latest, load_database_file, DatabaseCheck,
+    },
+    stats::deck_stats,
+    trade::{find_surplus, trade_list_string},
+    value::{collection_value, write_value_csv, DEFAULT_TOP_NUM},
+};
+
+/// usage text printed for the help command or when a command is not recognized
+pub const USAGE_STR: &str = "Usage: decklist [COMMAND] [OPTIONS]
+
+Run without a command to start the TUI.
+
+Commands:
+  value     Total value of the collection, broken down by set, rarity, color and format
+              --top <N>            number of most valuable cards to list (default 10)
+              --csv <FILE>         also export the full report as CSV
+  trade     Surplus cards beyond a playset that aren't used by saved decks
+              [DECK]...            extra decklists to keep cards for, besides saved_decks
+              --format <FORMAT>    moxfield, deckbox or text (default from config.toml)
+              --min <VALUE>        leave out cards worth less than this
+              --singleton          keep 1 copy of each card instead of a playset
+              --out <FILE>         write the trade list to a file instead of the screen
+  search    Search the card database with Scryfall style queries, showing how many you own
+              <QUERY>...           e.g. t:creature c:g cmc<=2 f:modern usd<1 is:owned
+  odds      Odds of drawing a card or enough lands by a turn, on the play and on the draw
+              <DECK>               decklist to use
+              --card <NAME>        card to look for
+              --min <N>            at least this many copies of the card (default 1)
+              --lands <N>          at least this many lands, needs the card database
+              --turn <T>           last turn to show (default 4)
+              --hands <N>          also draw N random opening hands
+              --trials <N>         also estimate the odds from N random draws for each turn
+              --seed <SEED>        seed for the random hands, the same seed gives the same hands
+  diff      Cards added, removed and changed between two versions of a decklist, and the cost
+            of the cards the new version needs beyond the old one and the collection
+              <OLD> <NEW>          the two decklists
+  missing   Cards in a decklist that aren't in the collection, with prices and a budget
+              <DECK>               decklist to check
+              --threshold <PRICE>  mark cards that cost more than this (default from config.toml)
+              --budget <PRICE>     list the cards to buy first within this budget, cards the
+                                   deck plays more copies of first (default from config.toml)
+  help      Print this message
+
+Common options:
+  --collection <FILE>  collection file to use instead of the one in config.toml";
+
+/// options that don't take a value
+const BOOL_FLAGS: [&str; 1] = ["singleton"];
+
+/// command line arguments split into the command, positional values, --options and --flags
+pub struct CliArgs {
+    pub command: String,
+    pub positional: Vec<String>,
+    pub options: HashMap<String, String>,
+    pub flags: Vec<String>,
+}
+
+impl CliArgs {
+    /// splits raw arguments (without the program name) into a CliArgs struct
+    pub fn parse(args: &[String]) -> Self {
+        let mut cli_args = CliArgs {
+            command: args.first().cloned().unwrap_or_default(),
+            positional: Vec::new(),
+            options: HashMap::new(),
+            flags: Vec::new(),
+        };
+        let mut iter = args.iter().skip(1);
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if BOOL_FLAGS.contains(&name) {
+                    cli_args.flags.push(name.to_string());
+                } else if let Some(value) = iter.next() {
+                    cli_args.options.insert(name.to_string(), value.clone());
+                } else {
+                    cli_args.flags.push(name.to_string());
+                }
+            } else {
+                cli_args.positional.push(arg.clone());
+            }
+        }
+        cli_args
+    }
+
+    /// returns true if --name was passed
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    /// parses the value of --name, or returns the default if it wasn't passed
+    pub fn parse_option<T: FromStr>(&self, name: &str, default: T) -> Result<T, Box<dyn Error>> {
+        match self.options.get(name) {
+            Some(value) => value
+                .parse::<T>()
+                .map_err(|_| format!("Invalid value for --{}: {}", name, value).into()),
+            None => Ok(default),
+        }
+    }
+}
+
+/// runs a single command from the command line instead of starting the TUI
+pub fn run_cli(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let cli_args = CliArgs::parse(args);
+    match cli_args.command.as_str() {
+        "value" => value_command(&cli_args),
+        "trade" => trade_command(&cli_args),
+        "search" => search_command(&cli_args),
+        "odds" => odds_command(&cli_args),
+        "diff" => diff_command(&cli_args),
+        "missing" => missing_command(&cli_args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE_STR);
+            Ok(())
+        }
+        other => Err(format!("Unknown command: {}\n\n{}", other, USAGE_STR).into()),
+    }
+}
+
+/// prints the collection value report
+fn value_command(args: &CliArgs) -> Result<(), Box<dyn Error>> {
+    let top_num = args.parse_option("top", DEFAULT_TOP_NUM)?;
+    let config = load_config();
+    let dc = load_database(&config)?;
+    let (printings, source) = load_printings(args, &config)?;
+    let currency = source.currency(&config.currency);
+    let value = task::block_on(collection_value(
+        &printings,
+        &dc.database_cards,
+        &dc.sets,
+        &dc.price_history,
+        currency.clone(),
+    ));
+    let symbol = currency.symbol();
+    println!("Collection value: {}{:.2}", symbol, value.total);
+    if !value.not_found.is_empty() {
+        println!(
+            "{} line items were not found in the database and are not counted.",
+            value.not_found.len()
+        );
+    }
+    if value.cheapest_count() > 0 {
+        println!(
+            "{} line items are valued at the cheapest printing of the card, their own printing isn't in the database.",
+            value.cheapest_count()
+        );
+    }
+    let breakdowns = [
+        ("By set", &value.by_set),
+        ("By rarity", &value.by_rarity),
+        ("By color", &value.by_color),
+        ("By format", &value.by_format),
+    ];
+    for (title, breakdown) in breakdowns {
+        println!("\n{}:", title);
+        for (name, amount) in breakdown.iter() {
+            println!("  {:<20} {}{:.2}", name, symbol, amount);
+        }
+    }
+    println!("\nTop {} cards:", top_num);
+    for card in value.top(top_num) {
+        println!(
+            "  {} {} ({}) [{:.2}] = {}{:.2}{}",
+            card.quantity,
+            card.name,
+            card.set,
+            card.unit_price,
+            symbol,
+            card.total,
+            if card.cheapest {
+                " (cheapest printing)"
+            } else {
+                ""
+            }
+        );
+    }
+    if let Some(csv_path) = args.options.get("csv") {
+        write_value_csv(&value, &PathBuf::from(csv_path))?;
+        println!("\nReport written to {}", csv_path);
+    }
+    Ok(())
+}
+
+/// prints or saves the trade list
+fn trade_command(args: &CliArgs) -> Result<(), Box<dyn Error>> {
+    let config = load_config();
+    let format = args.parse_option("format", config.trade_format.clone())?;
+    let min_value = args.parse_option("min", config.trade_min_value)?;
+    let singleton = args.flag("singleton") || config.trade_singleton;
+    let dc = load_database(&config)?;
+    let (printings, source) = load_printings(args, &config)?;
+    let currency = source.currency(&config.currency);
+    // a deck given on the command line that's also a saved deck is only counted once
+    let mut paths: Vec<PathBuf> = config.saved_decks.iter().map(|p| p.to_path_buf()).collect();
+    for path in args.positional.iter().map(PathBuf::from) {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    let mut decks = Vec::new();
+    for path in paths.iter() {
+        decks.push(read_decklist(path.to_string_lossy().to_string())?);
+    }
+    let trade_cards = task::block_on(find_surplus(
+        &printings,
+        &decks,
+        &dc.database_cards,
+        &dc.sets,
+        currency.clone(),
+        singleton,
+        min_value,
+    ));
+    let trade_string = trade_list_string(&trade_cards, &format);
+    match args.options.get("out") {
+        Some(out) => {
+            fs::write(out, trade_string)?;
+            println!(
+                "{} cards worth {}{:.2} written to {}",
+                trade_cards.iter().map(|c| c.quantity).sum::<u64>(),
+                currency.symbol(),
+                trade_cards.iter().map(|c| c.total()).sum::<f64>(),
+                out
+            );
+        }
+        None => print!("{}", trade_string),
+    }
+    Ok(())
+}
+
+/// prints every card in the database that matches the search query
+fn search_command(args: &CliArgs) -> Result<(), Box<dyn Error>> {
+    let query_str = args.positional.join(" ");
+    if query_str.is_empty() {
+        return Err(format!("No search query given.\n{}", SEARCH_HELP).into());
+    }
+    let query = query_str
+        .parse::<SearchQuery>()
+        .map_err(|e| format!("{}\n{}", e, SEARCH_HELP))?;
+    let config = load_config();
+    let dc = load_database(&config)?;
+    // the owned column is only filled in when there is a collection to check
+    let collection = match load_printings(args, &config) {
+        Ok((printings, _)) => squash_collection(printings),
+        Err(e) => {
+            eprintln!("{}", e);
+            Vec::new()
+        }
+    };
+    let results = task::block_on(search_database(
+        &query,
+        &dc.database_cards,
+        &dc.sets,
+        &collection,
+        config.currency.clone(),
+    ));
+    let symbol = config.currency.symbol();
+    for result in results.iter() {
+        let price = match result.price {
+            Some(p) => format!("{}{:.2}", symbol, p),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:>3} owned  {} {} | {} | {} {} | {}",
+            result.owned,
+            result.name,
+            result.mana_cost,
+            result.type_line,
+            result.set,
+            result.rarity,
+            price
+        );
+    }
+    println!("{} cards found", results.len());
+    Ok(())
+}
+
+/// prints draw odds and sample hands for a decklist
+fn odds_command(args: &CliArgs) -> Result<(), Box<dyn Error>> {
+    let deck_path = match args.positional.first() {
+        Some(p) => p.clone(),
+        None => return Err(format!("No decklist given.\n\n{}", USAGE_STR).into()),
+    };
+    let decklist = main_deck(&read_decklist(deck_path)?);
+    let deck_size: u64 = decklist.iter().map(|c| c.quantity).sum();
+    let turns = args.parse_option("turn", 4)?;
+    let hand_count = args.parse_option("hands", 0)?;
+    let seed = args.parse_option("seed", 1)?;
+    let trials = args.parse_option("trials", 0)?;
+    if !args.options.contains_key("card") && !args.options.contains_key("lands") && hand_count == 0
+    {
+        return Err("Nothing to calculate.  Use --card, --lands or --hands.".into());
+    }
+    println!("{} cards in the main deck", deck_size);
+    if let Some(card_name) = args.options.get("card") {
+        let safe_name = make_safe_name(card_name, true).to_lowercase();
+        let copies: u64 = decklist
+            .iter()
+            .filter(|c| make_safe_name(&c.name, true).to_lowercase() == safe_name)
+            .map(|c| c.quantity)
+            .sum();
+        if copies == 0 {
+            return Err(format!("{} is not in the decklist.", card_name).into());
+        }
+        let at_least = args.parse_option("min", 1)?;
+        println!(
+            "\nAt least {} {} ({} in the deck):",
+            at_least, card_name, copies
+        );
+        print_odds(deck_size, copies, at_least, turns, trials, seed);
+    }
+    if args.options.contains_key("lands") {
+        let at_least = args.parse_option("lands", 0)?;
+        let config = load_config();
+        let dc = load_database(&config)?;
+        let stats = task::block_on(deck_stats(&decklist, &dc.database_cards));
+        println!(
+            "\nAt least {} lands ({} in the deck):",
+            at_least, stats.lands
+        );
+        print_odds(deck_size, stats.lands, at_least, turns, trials, seed);
+    }
+    for (i, hand) in sample_hands(&decklist, HAND_SIZE, hand_count, seed)
+        .iter()
+        .enumerate()
+    {
+        println!("\nSample hand {} (seed {}):", i + 1, seed);
+        for card in hand {
+            println!("  {}", card);
+        }
+    }
+    Ok(())
+}
+
+/// prints the changes between two decklists and the cards needed to update the deck
+fn diff_command(args: &CliArgs) -> Result<(), Box<dyn Error>> {
+    let (old_path, new_path) = match args.positional.as_slice() {
+        [old, new] => (old.clone(), new.clone()),
+        _ => return Err(format!("diff needs two decklists.\n\n{}", USAGE_STR).into()),
+    };
+    let old = read_decklist(old_path)?;
+    let new = read_decklist(new_path)?;
+    let config = load_config();
+    let dc = load_database(&config)?;
+    let collection = match load_printings(args, &config) {
+        Ok((printings, _)) => squash_collection(printings),
+        Err(e) => {
+            eprintln!("{}  Only the old decklist counts as owned.", e);
+            Vec::new()
+        }
+    };
+    let diff = deck_diff(
+        &old,
+        &new,
+        &collection,
+        &dc.database_cards,
+        &config.currency,
+    );
+    if diff.changes.is_empty() {
+        println!("The decklists are the same.");
+        return Ok(());
+    }
+    let mut section = None;
+    for change in diff.changes.iter() {
+        if section != Some(change.section) {
+            println!("\n{}:", change.section);
+            section = Some(change.section);
+        }
+        println!(
+            "  {} {} -> {} {}",
+            change.symbol(),
+            change.old,
+            change.new,
+            change.name
+        );
+    }
+    let symbol = config.currency.symbol();
+    println!("\nNeeded for the new version:");
+    for need in diff.needs.iter() {
+        let price = match need.unit_price {
+            Some(p) => format!("{}{:.2}", symbol, p * need.to_buy as f64),
+            None => "not in database".to_string(),
+        };
+        println!(
+            "  {} {} (+{}, own {}) {}",
+            need.to_buy, need.name, need.added, need.owned, price
+        );
+    }
+    println!("Total: {}{:.2}", symbol, diff.total_cost());
+    Ok(())
+}
+
+/// prints the missing cards of a decklist with their prices and what to buy within a budget
+fn missing_command(args: &CliArgs) -> Result<(), Box<dyn Error>> {
+    let deck_path = match args.positional.first() {
+        Some(p) => p.clone(),
+        None => return Err(format!("missing needs a decklist.\n\n{}", USAGE_STR).into()),
+    };
+    let decklist = read_decklist(deck_path.clone())?;
+    let config = load_config();
+    let threshold = args.parse_option("threshold", config.price_threshold)?;
+    let budget = args.parse_option("budget", config.deck_budget)?;
+    let (printings, source) = load_printings(args, &config)?;
+    let collection = squash_collection(printings);
+    let currency = source.currency(&config.currency);
+    let dc = load_database(&config)?;
+    let missing =
+        task::block_on(find_missing_cards(collection, decklist.clone())).unwrap_or_default();
+    if missing.is_empty() {
+        println!("No missing cards!");
+        return Ok(());
+    }
+    let prices = unit_prices(&missing, &dc.database_cards, &currency);
+    let symbol = currency.symbol();
+    let width = missing
+        .iter()
+        .map(|c| c.to_string().chars().count())
+        .max()
+        .unwrap_or(0);
+    println!("Missing from {}:", deck_path);
+    let mut total = 0.0;
+    for (card, price) in missing.iter().zip(prices.iter()) {
+        let line = match price {
+            Some(p) => {
+                total += p * card.quantity as f64;
+                format!(
+                    "{}{:.2} each, {}{:.2}",
+                    symbol,
+                    p,
+                    symbol,
+                    p * card.quantity as f64
+                )
+            }
+            None => "not in database".to_string(),
+        };
+        let unavailable = match (source.game(), match_card(&card.name, &dc.database_cards)) {
+            (Some(game), Some(m)) if !m.in_game(&game) => format!("  not on {}", source.label()),
+            _ => String::new(),
+        };
+        let marker = if over_threshold(*price, threshold) {
+            format!("  over {}{:.2}", symbol, threshold)
+        } else {
+            String::new()
+        };
+        println!(
+            "  {:<width$}  {}{}{}",
+            card.to_string(),
+            line,
+            marker,
+            unavailable
+        );
+    }
+    println!("Total: {}{:.2}", symbol, total);
+    if budget <= 0.0 {
+        return Ok(());
+    }
+    if total > budget {
+        println!(
+            "Budget {}{:.2}: {}{:.2} over",
+            symbol,
+            budget,
+            symbol,
+            total - budget
+        );
+    } else {
+        println!(
+            "Budget {}{:.2}: {}{:.2} under",
+            symbol,
+            budget,
+            symbol,
+            budget - total
+        );
+    }
+    let plan = budget_plan(&missing, &decklist, &prices, budget);
+    println!(
+        "\nBuy first, {} copies for {}{:.2} ({}{:.2} left):",
+        plan.copies(),
+        symbol,
+        plan.cost,
+        symbol,
+        plan.left()
+    );
+    for (card, buy) in missing.iter().zip(plan.buy.iter()) {
+        if *buy > 0 {
+            println!("  {} {}", buy, card.name);
+        }
+    }
+    if plan.skipped > 0 {
+        println!(
+            "{} cards aren't in the database and were left out.",
+            plan.skipped
+        );
+    }
+    Ok(())
+}
+
+/// prints the odds for every turn up to the last one, on the play and on the draw
+/// with trials, the sampled estimates are printed next to the exact odds
+fn print_odds(deck_size: u64, successes: u64, at_least: u64, turns: u64, trials: u64, seed: u64) {
+    if trials > 0 {
+        println!(
+            "  Turn    Play    Draw  Sampled play/draw ({} draws, seed {})",
+            trials, seed
+        );
+    } else {
+        println!("  Turn    Play    Draw");
+    }
+    for turn in 1..=turns {
+        let mut line = format!(
+            "  {:>4} {:>6.1}% {:>6.1}%",
+            turn,
+            prob_by_turn(deck_size, successes, at_least, turn, true) * 100.0,
+            prob_by_turn(deck_size, successes, at_least, turn, false) * 100.0
+        );
+        if trials > 0 {
+            let sampled = |on_play| {
+                let draws = cards_seen(turn, on_play);
+                sampled_prob_at_least(deck_size, successes, draws, at_least, trials, seed) * 100.0
+            };
+            line += &format!(" {:>8.1}% {:>6.1}%", sampled(true), sampled(false));
+        }
+        println!("{}", line);
+    }
+}
+
+/// loads config.toml, falling back to the default settings the same way the TUI does
+fn load_config() -> DecklistConfig {
+    match ProjectDirs::from("", "", "decklist") {
+        Some(project_dir) => task::block_on(config_check(project_dir)).config,
+        None => DecklistConfig::default(),
+    }
+}
+
+/// finds and loads the card database, downloading a new one from Scryfall if needed
+fn load_database(config: &DecklistConfig) -> Result<DatabaseCheck, Box<dyn Error>> {
+    if !config.use_database {
+        return Err("This command needs the card database, but use_database is false.".into());
+    }
+    let data_path = config.database_path.to_path_buf();
+    if !data_path.exists() {
+        return Err(format!("Database directory {:?} does not exist.", data_path).into());
+    }
+    let mut dc = task::block_on(database_check(data_path.clone(), config.database_age_limit));
+    if dc.need_dl {
+        eprintln!("{}", dc.database_status);
+        dc = task::block_on(dl_scryfall_latest(dc));
+    }
+    if !dc.ready_load {
+        return Err(dc.database_status.into());
+    }
+    eprintln!("Loading {} ...", dc.filename);
+    let dc = task::block_on(load_database_file(dc, config.currency.clone()));
+    if dc.database_cards.is_empty() {
+        return Err(dc.database_status.into());
+    }
+    Ok(dc)
+}
+
+/// loads the collection given with --collection, or the one saved in config.toml
+fn load_printings(
+    args: &CliArgs,
+    config: &DecklistConfig,
+) -> Result<(Vec<CollectionCard>, CollectionSource), Box<dyn Error>> {
+    let path = match args.options.get("collection") {
+        Some(p) => p.clone(),
+        None => match &config.collection_path {
+            Some(p) => p.to_string_lossy().to_string(),
+            None => {
+                return Err(
+                    "No collection file given.  Use --collection or set collection_path in config.toml."
+                        .into(),
+                )
+            }
+        },
+    };
+    task::block_on(read_collection_printings(path))
+}
@@ -9,16 +9,45 @@ use csv::ReaderBuilder;
 use diacritics::remove_diacritics;
 use serde::Deserialize;
 
-use crate::database::scryfall::{make_safe_name, match_card, Legality, ScryfallCard};
+use crate::database::scryfall::{
+    make_safe_name, match_card, GameFormat, Legality, PriceType, ScryfallCard,
+};
 
 /// simple card format for collections and decklists
-/// just the card name and the quantity
-#[derive(Deserialize, Clone, Debug)]
+/// just the card name and the quantity, plus the printing when the collection file has it
+#[derive(Deserialize, Clone, Debug, Default)]
 pub struct CollectionCard {
     #[serde(rename = "Name")]
     pub name: String,
     #[serde(rename = "Count")]
     pub quantity: u64,
+    #[serde(rename = "Edition", default)]
+    pub edition: String, // set code
+    #[serde(rename = "Collector Number", default)]
+    pub collector_number: String,
+    #[serde(rename = "Foil", default)]
+    pub foil: String, // "foil", "etched" or blank
+    #[serde(skip)]
+    pub section: DeckSection, // only used by decklists
+}
+
+/// part of the decklist a card is in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DeckSection {
+    Commander,
+    #[default]
+    Main,
+    Sideboard,
+}
+
+impl Display for DeckSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckSection::Commander => write!(f, "Commander"),
+            DeckSection::Main => write!(f, "Main"),
+            DeckSection::Sideboard => write!(f, "Sideboard"),
+        }
+    }
 }
 
 impl Display for CollectionCard {
@@ -27,9 +56,194 @@ impl Display for CollectionCard {
     }
 }
 
+/// where a collection file was exported from
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CollectionSource {
+    #[default]
+    Paper, // Moxfield CSV
+    Mtgo,  // MTGO .dek or CSV
+    Arena, // text export, one "4 Card Name (SET) 123" line per card
+}
+
+impl CollectionSource {
+    /// guesses the source from the file extension, and the header for CSV files
+    pub fn detect(file_name: &str) -> CollectionSource {
+        let lower = file_name.to_lowercase();
+        if lower.ends_with(".dek") {
+            CollectionSource::Mtgo
+        } else if lower.ends_with(".txt") {
+            CollectionSource::Arena
+        } else {
+            // MTGO names its columns "Card Name" and "Quantity", Moxfield "Name" and "Count"
+            let header = fs::read_to_string(file_name)
+                .ok()
+                .and_then(|text| text.lines().next().map(|l| l.to_string()))
+                .unwrap_or_default();
+            if header.contains("Card Name") {
+                CollectionSource::Mtgo
+            } else {
+                CollectionSource::Paper
+            }
+        }
+    }
+
+    /// the game the cards have to be in, None for paper collections
+    pub fn game(&self) -> Option<GameFormat> {
+        match self {
+            CollectionSource::Paper => None,
+            CollectionSource::Mtgo => Some(GameFormat::MTGO),
+            CollectionSource::Arena => Some(GameFormat::Arena),
+        }
+    }
+
+    /// MTGO cards are bought with Tix, everything else uses the config currency
+    pub fn currency(&self, config_currency: &PriceType) -> PriceType {
+        match self {
+            CollectionSource::Mtgo => PriceType::Tix,
+            _ => config_currency.clone(),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CollectionSource::Paper => "Paper",
+            CollectionSource::Mtgo => "MTGO",
+            CollectionSource::Arena => "Arena",
+        }
+    }
+}
+
+/// one row of an MTGO collection CSV
+#[derive(Deserialize)]
+struct MtgoCsvCard {
+    #[serde(rename = "Card Name")]
+    name: String,
+    #[serde(rename = "Quantity")]
+    quantity: u64,
+    #[serde(rename = "Set", default)]
+    edition: String,
+    #[serde(rename = "Collector #", default)]
+    collector_number: String,
+}
+
+/// reads a collection file of any supported source, one line item per printing
+pub async fn read_collection_printings(
+    file_name: String,
+) -> Result<(Vec<CollectionCard>, CollectionSource), Box<dyn Error>> {
+    let source = CollectionSource::detect(&file_name);
+    let printings = match source {
+        CollectionSource::Paper => read_moxfield_printings(file_name).await?,
+        CollectionSource::Mtgo if file_name.to_lowercase().ends_with(".dek") => {
+            read_mtgo_dek(file_name)?
+        }
+        CollectionSource::Mtgo => read_mtgo_csv(file_name)?,
+        CollectionSource::Arena => read_arena_collection(file_name)?,
+    };
+    Ok((printings, source))
+}
+
+/// reads an MTGO .dek file, every <Cards> element is a line item
+/// the Sideboard attribute is ignored, a collection export has no sideboard
+fn read_mtgo_dek(file_name: String) -> Result<Vec<CollectionCard>, Box<dyn Error>> {
+    let file_str = fs::read_to_string(file_name)?;
+    let mut collection = Vec::new();
+    for element in file_str.split("<Cards ").skip(1) {
+        let element = element.split('>').next().unwrap_or_default();
+        let name = match xml_attribute(element, "Name") {
+            Some(n) => n,
+            None => continue,
+        };
+        let quantity = xml_attribute(element, "Quantity")
+            .ok_or(format!("No quantity for {} in .dek file", name))?
+            .parse::<u64>()?;
+        collection.push(CollectionCard {
+            name,
+            quantity,
+            ..Default::default()
+        });
+    }
+    Ok(collection)
+}
+
+/// value of an attribute in the inside of an XML tag, unescaped
+fn xml_attribute(element: &str, attribute: &str) -> Option<String> {
+    let start = element.find(&format!(" {}=\"", attribute))? + attribute.len() + 3;
+    let end = element[start..].find('"')? + start;
+    Some(
+        element[start..end]
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// reads an MTGO collection CSV
+fn read_mtgo_csv(file_name: String) -> Result<Vec<CollectionCard>, Box<dyn Error>> {
+    let file = File::open(file_name)?;
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .delimiter(b',')
+        .from_reader(file);
+    let mut collection = Vec::new();
+    for result in reader.deserialize() {
+        let record: MtgoCsvCard = result?;
+        collection.push(CollectionCard {
+            name: record.name,
+            quantity: record.quantity,
+            edition: record.edition.to_lowercase(),
+            collector_number: record.collector_number,
+            ..Default::default()
+        });
+    }
+    Ok(collection)
+}
+
+/// reads an Arena collection text export with lines like "4 Card Name (SET) 123"
+/// the set and collector number are optional, lines that don't start with a number are skipped
+fn read_arena_collection(file_name: String) -> Result<Vec<CollectionCard>, Box<dyn Error>> {
+    let file_str = fs::read_to_string(file_name)?;
+    let mut collection = Vec::new();
+    for line in file_str.lines() {
+        let (count, rest) = match line.trim().split_once(' ') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let quantity = match count.trim_end_matches('x').parse::<u64>() {
+            Ok(q) => q,
+            Err(_) => continue, // "Deck", "Sideboard" and other headers
+        };
+        let (name, edition, collector_number) = match rest.rfind(" (") {
+            Some(i) if rest[i..].contains(')') => {
+                let (set, number) = rest[i + 2..].split_once(')').unwrap_or_default();
+                (&rest[..i], set.to_lowercase(), number.trim().to_string())
+            }
+            _ => (rest, String::new(), String::new()),
+        };
+        collection.push(CollectionCard {
+            name: name.trim().replace(" /// ", " // "),
+            quantity,
+            edition,
+            collector_number,
+            ..Default::default()
+        });
+    }
+    Ok(collection)
+}
+
 /// reads in Moxfield collection CSV and turns it into a Vec<CollectionCard>
 pub async fn read_moxfield_collection(
     file_name: String,
+) -> Result<Vec<CollectionCard>, Box<dyn Error>> {
+    let collection = read_moxfield_printings(file_name).await?;
+    let squashed = squash_collection(collection);
+    Ok(squashed)
+}
+
+/// reads in Moxfield collection CSV with one line item per printing, the way Moxfield exports it
+pub async fn read_moxfield_printings(
+    file_name: String,
 ) -> Result<Vec<CollectionCard>, Box<dyn Error>> {
     let file = File::open(file_name)?;
     let mut reader = ReaderBuilder::new()
@@ -42,14 +256,13 @@ pub async fn read_moxfield_collection(
         let record: CollectionCard = result?;
         collection.push(record);
     }
-    let squashed = squash_collection(collection);
-    Ok(squashed)
+    Ok(collection)
 }
 
 /// Moxfield collection treats different printings of the same card as individual line items, but
 /// that's not a distinction we need for this program.  This function "squashes" the quantities of
 /// all printings of the same card into a single line item.
-fn squash_collection(collection_in: Vec<CollectionCard>) -> Vec<CollectionCard> {
+pub fn squash_collection(collection_in: Vec<CollectionCard>) -> Vec<CollectionCard> {
     let mut collection_out: Vec<CollectionCard> = Vec::new();
     for card in collection_in.iter() {
         if !collection_out.is_empty() {
@@ -78,16 +291,73 @@ fn squash_collection(collection_in: Vec<CollectionCard>) -> Vec<CollectionCard>
     collection_out
 }
 
+/// markers after a card name that make it a commander
+pub const COMMANDER_MARKERS: [&str; 2] = ["*CMDR*", "[Commander]"];
+
+/// section labels and the commander marker a decklist file was written with, so edits can be
+/// saved the same way
+#[derive(Clone, Debug, Default)]
+pub struct DeckLabels {
+    pub labels: HashMap<DeckSection, String>, // label lines as they are in the file
+    pub commander_marker: Option<String>,
+}
+
+/// the section a label line starts, with or without a colon or a leading //
+fn section_label(line: &str) -> Option<DeckSection> {
+    let label = line
+        .trim()
+        .trim_start_matches("//")
+        .trim()
+        .trim_end_matches(':')
+        .to_lowercase();
+    match label.as_str() {
+        "sideboard" => Some(DeckSection::Sideboard),
+        "commander" | "commanders" => Some(DeckSection::Commander),
+        "deck" | "main" | "mainboard" => Some(DeckSection::Main),
+        _ => None,
+    }
+}
+
+/// card name without its commander marker, and the marker if it had one
+fn strip_commander_marker(card_name: &str) -> (&str, Option<&'static str>) {
+    for marker in COMMANDER_MARKERS {
+        if let Some(name) = card_name.strip_suffix(marker) {
+            return (name.trim(), Some(marker));
+        }
+    }
+    (card_name, None)
+}
+
+/// finds the section labels and commander marker used in the text of a decklist file
+pub fn read_deck_labels(file_str: &str) -> DeckLabels {
+    let mut deck_labels = DeckLabels::default();
+    for line in file_str.lines() {
+        if let Some(section) = section_label(line) {
+            deck_labels
+                .labels
+                .entry(section)
+                .or_insert_with(|| line.trim().to_string());
+        } else if let (_, Some(marker)) = strip_commander_marker(line.trim()) {
+            deck_labels.commander_marker = Some(marker.to_string());
+        }
+    }
+    deck_labels
+}
+
 /// reads in a decklist file in this format:
 /// ## Card Name
-/// safely skips over Sideboard label, blank lines, etc.
+/// safely skips over blank lines, etc.
+/// cards after the Sideboard label are marked as sideboard cards
+/// cards after a Commander label, or marked with *CMDR* or [Commander], are the commanders, and
+/// a Deck or Main label goes back to the main deck
 pub fn read_decklist(file_name: String) -> Result<Vec<CollectionCard>, Box<dyn Error>> {
     let mut decklist: Vec<CollectionCard> = Vec::new();
     let file_str = fs::read_to_string(file_name)?;
     let rows: Vec<&str> = file_str.split('\n').collect();
+    let mut section = DeckSection::Main;
     for line in rows.iter() {
-        // check for "Sideboard" text
-        if line.to_lowercase() == "sideboard" {
+        if let Some(label_section) = section_label(line) {
+            section = label_section;
             continue;
         }
         // separate by first space to get number and name
@@ -98,14 +368,29 @@ pub fn read_decklist(file_name: String) -> Result<Vec<CollectionCard>, Box<dyn E
         // convert number to integer
         let str_num = words[0].parse::<u64>()?;
         let card_name = words[1..].join(" ");
+        let (card_name, card_section) = match strip_commander_marker(&card_name) {
+            (name, Some(_)) => (name.to_string(), DeckSection::Commander),
+            (name, None) => (name.to_string(), section),
+        };
         decklist.push(CollectionCard {
             name: card_name,
             quantity: str_num,
+            section: card_section,
+            ..Default::default()
         });
     }
     Ok(decklist)
 }
 
+/// the cards in the main deck, leaving out the sideboard
+pub fn main_deck(decklist: &[CollectionCard]) -> Vec<CollectionCard> {
+    decklist
+        .iter()
+        .filter(|c| c.section == DeckSection::Main)
+        .cloned()
+        .collect()
+}
+
 /// compares the decklist to the loaded collection
 /// outputs a list of missing cards
 pub async fn find_missing_cards(
@@ -164,15 +449,22 @@ pub async fn find_missing_cards(
 
 /// compares missing card to Scryfall database (if included)
 /// if the card isn't found in the database, prompt the user to check the spelling
+/// with a digital collection, cards that aren't in its game are flagged too
 pub fn check_missing(
     database: &HashMap<String, ScryfallCard>,
     missing_card: &CollectionCard,
+    source: CollectionSource,
 ) -> String {
     // NOTE: making this always true in case someone puts the full dual card names
     // could check for slashes to make it cleaner, but whatevs, the function already does that
     let missing_safe = make_safe_name(&missing_card.name, true);
-    if database.contains_key(&missing_safe) {
-        "".to_string()
+    if let Some(matched) = database.get(&missing_safe) {
+        match source.game() {
+            Some(game) if !matched.in_game(&game) => {
+                format!(" <------ This card isn't on {}.", source.label())
+            }
+            _ => "".to_string(),
+        }
     } else {
         " <------ This card was not found in database.  Check spelling?".to_string()
     }
@@ -202,6 +494,7 @@ pub struct FormatLegal {
     pub oldschool: bool,
     pub premodern: bool,
     pub predh: bool,
+    pub not_in_game: Vec<String>, // cards a digital collection's game doesn't have
 }
 
 impl Default for FormatLegal {
@@ -228,6 +521,37 @@ impl Default for FormatLegal {
             oldschool: true,
             premodern: true,
             predh: true,
+            not_in_game: Vec::new(),
+        }
+    }
+}
+
+impl FormatLegal {
+    /// looks up a format by its Scryfall name (see FORMAT_NAMES)
+    pub fn get(&self, format: &str) -> Option<bool> {
+        match format {
+            "standard" => Some(self.standard),
+            "future" => Some(self.future),
+            "historic" => Some(self.historic),
+            "timeless" => Some(self.timeless),
+            "gladiator" => Some(self.gladiator),
+            "pioneer" => Some(self.pioneer),
+            "modern" => Some(self.modern),
+            "legacy" => Some(self.legacy),
+            "pauper" => Some(self.pauper),
+            "vintage" => Some(self.vintage),
+            "penny" => Some(self.penny),
+            "commander" => Some(self.commander),
+            "oathbreaker" => Some(self.oathbreaker),
+            "standardbrawl" => Some(self.standardbrawl),
+            "brawl" => Some(self.brawl),
+            "alchemy" => Some(self.alchemy),
+            "paupercommander" => Some(self.paupercommander),
+            "duel" => Some(self.duel),
+            "oldschool" => Some(self.oldschool),
+            "premodern" => Some(self.premodern),
+            "predh" => Some(self.predh),
+            _ => None,
         }
     }
 }
@@ -243,14 +567,20 @@ fn convert_legal(legal: Legality) -> bool {
 }
 
 /// checks decklist for legality, and outputs a structure with the results
+/// with a digital collection, the cards that aren't in its game are listed apart from the formats
 pub async fn check_legality(
     decklist: &[CollectionCard],
     database: &HashMap<String, ScryfallCard>,
+    game: Option<GameFormat>,
 ) -> FormatLegal {
     // TODO: check number of cards?
     let mut legal = FormatLegal::default();
     for card in decklist {
         if let Some(matched) = match_card(&card.name, &database) {
+            if game.is_some_and(|g| !matched.in_game(&g)) && !legal.not_in_game.contains(&card.name)
+            {
+                legal.not_in_game.push(card.name.clone());
+            }
             // go through every format - if still true, check current card legality
             if legal.standard {
                 legal.standard = convert_legal(matched.legalities.standard); // only go to false
@@ -319,3 +649,124 @@ pub async fn check_legality(
     }
     legal
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// writes a collection file to the temp directory and reads it back with its source
+    fn read(file_name: &str, contents: &str) -> (Vec<CollectionCard>, CollectionSource) {
+        let path = std::env::temp_dir().join(format!("{}_{}", std::process::id(), file_name));
+        fs::write(&path, contents).unwrap();
+        let read = async_std::task::block_on(read_collection_printings(
+            path.to_string_lossy().to_string(),
+        ));
+        fs::remove_file(&path).unwrap();
+        read.unwrap()
+    }
+
+    fn names(cards: &[CollectionCard]) -> Vec<(&str, u64)> {
+        cards
+            .iter()
+            .map(|c| (c.name.as_str(), c.quantity))
+            .collect()
+    }
+
+    #[test]
+    fn reads_mtgo_dek() {
+        let (cards, source) = read(
+            "collection.dek",
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<Deck xmlns:xsd="http://www.w3.org/2001/XMLSchema">
+  <NetDeckID>0</NetDeckID>
+  <Cards CatID="1" Quantity="4" Sideboard="false" Name="Lightning Bolt" Annotation="0" />
+  <Cards CatID="2" Quantity="1" Sideboard="true" Name="Kongming, &quot;Sleeping Dragon&quot;" />
+</Deck>"#,
+        );
+        assert_eq!(source, CollectionSource::Mtgo);
+        assert_eq!(
+            names(&cards),
+            vec![("Lightning Bolt", 4), ("Kongming, \"Sleeping Dragon\"", 1)]
+        );
+    }
+
+    #[test]
+    fn reads_mtgo_csv() {
+        let (cards, source) = read(
+            "collection.csv",
+            "Card Name,Quantity,ID #,Rarity,Set,Collector #,Premium\n\
+             Lightning Bolt,4,123,Common,M11,146,No\n\
+             \"Fire // Ice\",2,456,Uncommon,MH2,290,No\n",
+        );
+        assert_eq!(source, CollectionSource::Mtgo);
+        assert_eq!(
+            names(&cards),
+            vec![("Lightning Bolt", 4), ("Fire // Ice", 2)]
+        );
+        assert_eq!(cards[0].edition, "m11");
+        assert_eq!(cards[0].collector_number, "146");
+    }
+
+    #[test]
+    fn reads_arena_text() {
+        let (cards, source) = read(
+            "collection.txt",
+            "Deck\n4 Lightning Bolt (STA) 42\n2x Counterspell\n\
+             1 Fire /// Ice (MH2) 290\n\nSideboard\n3 Duress (M19)\n",
+        );
+        assert_eq!(source, CollectionSource::Arena);
+        assert_eq!(
+            names(&cards),
+            vec![
+                ("Lightning Bolt", 4),
+                ("Counterspell", 2),
+                ("Fire // Ice", 1),
+                ("Duress", 3)
+            ]
+        );
+        assert_eq!(
+            (
+                cards[0].edition.as_str(),
+                cards[0].collector_number.as_str()
+            ),
+            ("sta", "42")
+        );
+        assert!(cards[1].edition.is_empty());
+        assert_eq!(
+            (
+                cards[3].edition.as_str(),
+                cards[3].collector_number.as_str()
+            ),
+            ("m19", "")
+        );
+    }
+
+    #[test]
+    fn cards_missing_from_the_game_keep_the_formats() {
+        let mut bolt = ScryfallCard::test_card("Lightning Bolt", "Instant", "{R}", "");
+        bolt.legalities.modern = Legality::Legal;
+        let mut island = ScryfallCard::test_card("Island", "Basic Land — Island", "", "");
+        island.legalities.modern = Legality::Legal;
+        island.games.push(GameFormat::Arena);
+        let database: HashMap<String, ScryfallCard> = [bolt, island]
+            .into_iter()
+            .map(|c| (make_safe_name(&c.name, true), c))
+            .collect();
+        let decklist: Vec<CollectionCard> = [("Lightning Bolt", 4), ("Island", 20)]
+            .into_iter()
+            .map(|(name, quantity)| CollectionCard {
+                name: name.to_string(),
+                quantity,
+                ..Default::default()
+            })
+            .collect();
+        let legal = async_std::task::block_on(check_legality(
+            &decklist,
+            &database,
+            Some(GameFormat::Arena),
+        ));
+        assert!(legal.modern);
+        assert!(!legal.pauper);
+        assert_eq!(legal.not_in_game, vec!["Lightning Bolt".to_string()]);
+    }
+}
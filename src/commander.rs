@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use crate::{
+    collection::{CollectionCard, DeckSection},
+    database::scryfall::{make_safe_name, Legality, MtGColors, ScryfallCard},
+};
+
+/// cards in a Commander deck, commanders included
+pub const DECK_SIZE: u64 = 100;
+
+/// least played cards listed for a deck
+pub const LEAST_PLAYED: usize = 3;
+
+/// how a deck holds up against the Commander deck building rules
+#[derive(Clone, Default)]
+pub struct CommanderCheck {
+    pub commanders: Vec<String>,
+    pub identity: Vec<MtGColors>, // combined color identity of the commanders
+    pub card_count: u64,
+    pub pair_problem: Option<String>, // commanders that can't lead the deck or can't be paired
+    pub off_identity: Vec<String>,
+    pub duplicates: Vec<String>,
+    pub banned: Vec<String>,
+    pub not_legal: Vec<String>, // not banned, but never legal in Commander, like Un-cards
+    pub not_found: Vec<String>,
+    pub average_rank: Option<f64>, // EDHREC rank of the cards besides the commanders
+    pub least_played: Vec<(String, i64)>, // highest EDHREC ranks first
+}
+
+impl CommanderCheck {
+    pub fn legal(&self) -> bool {
+        self.card_count == DECK_SIZE
+            && self.pair_problem.is_none()
+            && self.off_identity.is_empty()
+            && self.duplicates.is_empty()
+            && self.banned.is_empty()
+            && self.not_legal.is_empty()
+    }
+
+    /// color identity as mana symbols, C for colorless
+    pub fn identity_str(&self) -> String {
+        if self.identity.is_empty() {
+            return "C".to_string();
+        }
+        [
+            (MtGColors::White, 'W'),
+            (MtGColors::Blue, 'U'),
+            (MtGColors::Black, 'B'),
+            (MtGColors::Red, 'R'),
+            (MtGColors::Green, 'G'),
+        ]
+        .into_iter()
+        .filter(|(color, _)| self.identity.contains(color))
+        .map(|(_, symbol)| symbol)
+        .collect()
+    }
+}
+
+/// checks a deck with a commander section, None if the deck doesn't have one
+/// the sideboard isn't part of the deck and is left out
+pub fn check_commander(
+    decklist: &[CollectionCard],
+    database: &HashMap<String, ScryfallCard>,
+) -> Option<CommanderCheck> {
+    let commander_cards: Vec<&CollectionCard> = decklist
+        .iter()
+        .filter(|c| c.section == DeckSection::Commander)
+        .collect();
+    if commander_cards.is_empty() {
+        return None;
+    }
+    let lookup = |name: &str| database.get(&make_safe_name(name, true));
+    let mut check = CommanderCheck {
+        commanders: commander_cards.iter().map(|c| c.name.clone()).collect(),
+        ..Default::default()
+    };
+    let commanders: Vec<&ScryfallCard> = commander_cards
+        .iter()
+        .filter_map(|c| lookup(&c.name))
+        .collect();
+    check.pair_problem = match commander_cards.iter().find(|c| lookup(&c.name).is_none()) {
+        Some(missing) => Some(format!("{} not found in the database", missing.name)),
+        None => pair_problem(&commanders),
+    };
+    for commander in commanders.iter() {
+        for color in commander.color_identity.iter().flatten() {
+            if !check.identity.contains(color) {
+                check.identity.push(color.clone());
+            }
+        }
+    }
+    let mut copies: HashMap<String, (String, u64)> = HashMap::new();
+    let mut ranked: Vec<(String, i64)> = Vec::new();
+    for card in decklist
+        .iter()
+        .filter(|c| c.section != DeckSection::Sideboard)
+    {
+        check.card_count += card.quantity;
+        let entry = copies
+            .entry(make_safe_name(&card.name, true))
+            .or_insert((card.name.clone(), 0));
+        entry.1 += card.quantity;
+        let matched = match lookup(&card.name) {
+            Some(m) => m,
+            None => {
+                check.not_found.push(card.name.clone());
+                continue;
+            }
+        };
+        if !matched
+            .color_identity
+            .iter()
+            .flatten()
+            .all(|c| check.identity.contains(c))
+        {
+            check.off_identity.push(card.name.clone());
+        }
+        match matched.legalities.commander {
+            Legality::Legal => {}
+            Legality::Banned => check.banned.push(card.name.clone()),
+            Legality::NotLegal | Legality::Restricted => check.not_legal.push(card.name.clone()),
+        }
+        // basic lands have ranks too, but say nothing about the deck
+        if card.section == DeckSection::Main
+            && matched.edhrec_rank > 0
+            && !any_number_allowed(matched)
+            && !ranked.iter().any(|(name, _)| *name == card.name)
+        {
+            ranked.push((card.name.clone(), matched.edhrec_rank));
+        }
+    }
+    if !ranked.is_empty() {
+        check.average_rank =
+            Some(ranked.iter().map(|(_, r)| *r as f64).sum::<f64>() / ranked.len() as f64);
+    }
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(LEAST_PLAYED);
+    check.least_played = ranked;
+    let mut duplicates: Vec<String> = copies
+        .into_iter()
+        .filter(|(safe_name, (_, quantity))| {
+            *quantity > 1 && !database.get(safe_name).is_some_and(any_number_allowed)
+        })
+        .map(|(_, (name, _))| name)
+        .collect();
+    duplicates.sort();
+    check.duplicates = duplicates;
+    Some(check)
+}
+
+/// basic lands and cards like Relentless Rats can have more than one copy
+fn any_number_allowed(card: &ScryfallCard) -> bool {
+    card.type_line.as_ref().is_some_and(|t| t.contains("Basic"))
+        || card
+            .face_oracle_text()
+            .contains("A deck can have any number of cards named")
+}
+
+/// what's wrong with the commander or the pair of commanders, if anything
+fn pair_problem(commanders: &[&ScryfallCard]) -> Option<String> {
+    match commanders {
+        [] => None,
+        [commander] => (!can_be_commander(commander))
+            .then(|| format!("{} can't be a commander", commander.name)),
+        [a, b] => {
+            for card in [a, b] {
+                let background = card
+                    .type_line
+                    .as_ref()
+                    .is_some_and(|t| t.contains("Background"));
+                if !background && !can_be_commander(card) {
+                    return Some(format!("{} can't be a commander", card.name));
+                }
+            }
+            (!can_pair(a, b) && !can_pair(b, a))
+                .then(|| format!("{} and {} can't be paired", a.name, b.name))
+        }
+        _ => Some("A deck can only have two commanders".to_string()),
+    }
+}
+
+/// legendary creatures, and cards that say they can be a commander
+fn can_be_commander(card: &ScryfallCard) -> bool {
+    card.type_line
+        .as_ref()
+        .is_some_and(|t| t.contains("Legendary") && t.contains("Creature"))
+        || card.face_oracle_text().contains("can be your commander")
+}
+
+/// partner, partner with, friends forever, choose a background and doctor's companion pairs
+fn can_pair(a: &ScryfallCard, b: &ScryfallCard) -> bool {
+    let (text_a, text_b) = (a.face_oracle_text(), b.face_oracle_text());
+    let has = |text: &str, ability: &str| text.lines().any(|l| l.starts_with(ability));
+    let type_has =
+        |card: &ScryfallCard, word: &str| card.type_line.as_ref().is_some_and(|t| t.contains(word));
+    let partner_a = partner_kind(&text_a);
+    (partner_a.is_some() && partner_a == partner_kind(&text_b))
+        || has(&text_a, &format!("Partner with {}", b.name))
+        || (has(&text_a, "Friends forever") && has(&text_b, "Friends forever"))
+        || (has(&text_a, "Choose a Background") && type_has(b, "Background"))
+        || (has(&text_a, "Doctor's companion") && type_has(b, "Time Lord Doctor"))
+}
+
+/// the kind of a plain "Partner" or a "Partner—Character select" line, only the same kinds pair
+fn partner_kind(oracle_text: &str) -> Option<&str> {
+    oracle_text.lines().find_map(|l| {
+        let rest = l.strip_prefix("Partner")?;
+        let rest = rest.split(" (").next().unwrap_or_default();
+        match rest.strip_prefix('—') {
+            Some(kind) => Some(kind),
+            None => rest.is_empty().then_some(""),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::scryfall::ScryfallCardFace;
+
+    fn commander(name: &str, identity: &[MtGColors], oracle_text: &str) -> ScryfallCard {
+        let mut card = ScryfallCard::test_card(name, "Legendary Creature — Human", "", oracle_text);
+        card.color_identity = Some(identity.to_vec());
+        card.legalities.commander = Legality::Legal;
+        card
+    }
+
+    fn database(cards: Vec<ScryfallCard>) -> HashMap<String, ScryfallCard> {
+        let mut island = ScryfallCard::test_card("Island", "Basic Land — Island", "", "");
+        island.color_identity = Some(vec![MtGColors::Blue]);
+        island.legalities.commander = Legality::Legal;
+        let mut sol_ring = ScryfallCard::test_card("Sol Ring", "Artifact", "{1}", "");
+        sol_ring.legalities.commander = Legality::Legal;
+        let mut bolt = ScryfallCard::test_card("Lightning Bolt", "Instant", "{R}", "");
+        bolt.color_identity = Some(vec![MtGColors::Red]);
+        bolt.legalities.commander = Legality::Legal;
+        let mut channel = ScryfallCard::test_card("Channel", "Sorcery", "{G}{G}", "");
+        channel.legalities.commander = Legality::Banned;
+        cards
+            .into_iter()
+            .chain([island, sol_ring, bolt, channel])
+            .map(|c| (make_safe_name(&c.name, true), c))
+            .collect()
+    }
+
+    fn deck(cards: &[(&str, u64, DeckSection)]) -> Vec<CollectionCard> {
+        cards
+            .iter()
+            .map(|(name, quantity, section)| CollectionCard {
+                name: name.to_string(),
+                quantity: *quantity,
+                section: *section,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn legal_deck() {
+        let database = database(vec![commander("Talrand", &[MtGColors::Blue], "")]);
+        let decklist = deck(&[
+            ("Talrand", 1, DeckSection::Commander),
+            ("Sol Ring", 1, DeckSection::Main),
+            ("Island", 98, DeckSection::Main),
+            ("Lightning Bolt", 4, DeckSection::Sideboard),
+        ]);
+        let check = check_commander(&decklist, &database).unwrap();
+        assert!(check.legal());
+        assert_eq!(check.card_count, 100);
+        assert_eq!(check.identity_str(), "U");
+        assert!(check_commander(&decklist[1..], &database).is_none());
+    }
+
+    #[test]
+    fn broken_rules() {
+        let database = database(vec![commander("Talrand", &[MtGColors::Blue], "")]);
+        let decklist = deck(&[
+            ("Talrand", 1, DeckSection::Commander),
+            ("Sol Ring", 2, DeckSection::Main),
+            ("Lightning Bolt", 1, DeckSection::Main),
+            ("Channel", 1, DeckSection::Main),
+            ("Unknown Card", 1, DeckSection::Main),
+            ("Island", 90, DeckSection::Main),
+        ]);
+        let check = check_commander(&decklist, &database).unwrap();
+        assert!(!check.legal());
+        assert_eq!(check.card_count, 96);
+        assert_eq!(check.duplicates, vec!["Sol Ring".to_string()]);
+        assert_eq!(check.off_identity, vec!["Lightning Bolt".to_string()]);
+        assert_eq!(check.banned, vec!["Channel".to_string()]);
+        assert_eq!(check.not_found, vec!["Unknown Card".to_string()]);
+        assert!(check.pair_problem.is_none());
+    }
+
+    #[test]
+    fn pairs() {
+        let partner = commander(
+            "Thrasios",
+            &[MtGColors::Green],
+            "Partner (You can have two...)",
+        );
+        let other = commander("Tymna", &[MtGColors::White], "Lifelink\nPartner");
+        let select = commander("Cloud", &[MtGColors::White], "Partner—Character select");
+        let select2 = commander("Tifa", &[MtGColors::Green], "Partner—Character select");
+        let plain = commander("Talrand", &[MtGColors::Blue], "");
+        let mut face_partner = commander("Flip Partner", &[MtGColors::Red], "");
+        face_partner.card_faces = vec![ScryfallCardFace {
+            name: "Flip Partner".to_string(),
+            mana_cost: String::new(),
+            type_line: None,
+            oracle_text: "Partner".to_string(),
+            colors: None,
+        }];
+        assert!(pair_problem(&[&partner, &other]).is_none());
+        assert!(pair_problem(&[&select, &select2]).is_none());
+        assert!(pair_problem(&[&partner, &face_partner]).is_none());
+        assert!(pair_problem(&[&partner, &select]).is_some());
+        assert!(pair_problem(&[&partner, &plain]).is_some());
+        assert!(pair_problem(&[&partner, &other, &plain]).is_some());
+        let mut not_legendary = plain.clone();
+        not_legendary.type_line = Some("Creature — Human".to_string());
+        assert!(pair_problem(&[&not_legendary]).is_some());
+        not_legendary.oracle_text = "Talrand can be your commander.".to_string();
+        assert!(pair_problem(&[&not_legendary]).is_none());
+    }
+
+    #[test]
+    fn any_number_of_copies() {
+        let mut rats =
+            ScryfallCard::test_card("Relentless Rats", "Creature — Rat", "{1}{B}{B}", "");
+        assert!(!any_number_allowed(&rats));
+        rats.oracle_text = "A deck can have any number of cards named Relentless Rats.".to_string();
+        assert!(any_number_allowed(&rats));
+        let island = ScryfallCard::test_card("Island", "Basic Land — Island", "", "");
+        assert!(any_number_allowed(&island));
+    }
+}
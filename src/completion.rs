@@ -0,0 +1,147 @@
+use std::collections::{HashMap, HashSet};
+
+use diacritics::remove_diacritics;
+
+use crate::{
+    collection::CollectionCard,
+    database::{
+        scryfall::MtGRarity,
+        sets::{ScryfallSet, SetPrinting},
+    },
+};
+
+/// limits set completion to a single rarity
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RarityFilter {
+    #[default]
+    All,
+    Common,
+    Uncommon,
+    Rare,
+    Mythic,
+}
+
+impl RarityFilter {
+    /// cycles to the next filter, used by the Sets tab
+    pub fn next(self) -> Self {
+        match self {
+            RarityFilter::All => RarityFilter::Common,
+            RarityFilter::Common => RarityFilter::Uncommon,
+            RarityFilter::Uncommon => RarityFilter::Rare,
+            RarityFilter::Rare => RarityFilter::Mythic,
+            RarityFilter::Mythic => RarityFilter::All,
+        }
+    }
+
+    fn allows(&self, rarity: &MtGRarity) -> bool {
+        match self {
+            RarityFilter::All => true,
+            RarityFilter::Common => *rarity == MtGRarity::Common,
+            RarityFilter::Uncommon => *rarity == MtGRarity::Uncommon,
+            RarityFilter::Rare => *rarity == MtGRarity::Rare,
+            RarityFilter::Mythic => *rarity == MtGRarity::Mythic,
+        }
+    }
+}
+
+/// how much of a single set is in the collection
+#[derive(Clone)]
+pub struct SetCompletion {
+    pub code: String,
+    pub name: String,
+    pub owned: usize, // distinct collector numbers, so variants count on their own
+    pub total: usize,
+    pub missing: Vec<SetPrinting>,
+}
+
+impl SetCompletion {
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.owned as f64 / self.total as f64 * 100.0
+        }
+    }
+}
+
+/// compares the printings in the collection to every set they come from
+/// only sets with at least one card in the collection are returned, most complete first
+/// printings are matched by set code and collector number, or by name for entries in the
+/// collection file without a collector number
+pub async fn set_completion(
+    printings: &[CollectionCard],
+    sets: &HashMap<String, ScryfallSet>,
+    filter: RarityFilter,
+) -> Vec<SetCompletion> {
+    // collector numbers owned in each set, and names of the entries without one
+    let mut owned_numbers: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut owned_names: HashMap<String, HashSet<String>> = HashMap::new();
+    for printing in printings {
+        if printing.edition.is_empty() || printing.quantity == 0 {
+            continue;
+        }
+        let code = printing.edition.to_lowercase();
+        let numbers = owned_numbers.entry(code.clone()).or_default();
+        if printing.collector_number.is_empty() {
+            owned_names
+                .entry(code)
+                .or_default()
+                .insert(remove_diacritics(&printing.name));
+        } else {
+            numbers.insert(printing.collector_number.clone());
+        }
+    }
+    let no_names = HashSet::new();
+    let mut completion = Vec::new();
+    for (code, numbers) in owned_numbers.iter() {
+        let set = match sets.get(code) {
+            Some(s) => s,
+            None => continue,
+        };
+        let names = owned_names.get(code).unwrap_or(&no_names);
+        // some sets have multiple printings with the same collector number (different styles)
+        let mut seen = HashSet::new();
+        let mut owned = 0;
+        let mut missing = Vec::new();
+        for printing in set.printings.iter() {
+            if !filter.allows(&printing.rarity) || !seen.insert(&printing.collector_number) {
+                continue;
+            }
+            if numbers.contains(&printing.collector_number)
+                || names.contains(&remove_diacritics(&printing.name))
+            {
+                owned += 1;
+            } else {
+                missing.push(printing.clone());
+            }
+        }
+        let total = owned + missing.len();
+        if owned == 0 {
+            continue;
+        }
+        missing.sort_by(|a, b| collector_order(&a.collector_number, &b.collector_number));
+        completion.push(SetCompletion {
+            code: set.code.clone(),
+            name: set.name.clone(),
+            owned,
+            total,
+            missing,
+        });
+    }
+    completion.sort_by(|a, b| {
+        b.percent()
+            .total_cmp(&a.percent())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    completion
+}
+
+/// sorts collector numbers numerically where possible (2 before 10), then alphabetically
+fn collector_order(a: &str, b: &str) -> std::cmp::Ordering {
+    let a_num: String = a.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let b_num: String = b.chars().take_while(|c| c.is_ascii_digit()).collect();
+    match (a_num.parse::<u64>(), b_num.parse::<u64>()) {
+        (Ok(x), Ok(y)) => x.cmp(&y).then_with(|| a.cmp(b)),
+        _ => a.cmp(b),
+    }
+}
@@ -3,7 +3,10 @@ use std::path::Path;
 use directories_next::ProjectDirs;
 use serde::{Deserialize, Serialize};
 
-use crate::database::scryfall::PriceType;
+use crate::{
+    database::scryfall::PriceType, keybindings::KeyBindings, missing::MissingFormat,
+    report::ReportFormat, trade::TradeFormat, tui::theme::PaletteName,
+};
 
 /// app config settings
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -14,6 +17,32 @@ pub struct DecklistConfig {
     pub database_num: u64,  // number of database files to keep around
     pub collection_path: Option<Box<Path>>, // path to latest collection file
     pub currency: PriceType,
+    #[serde(default)]
+    pub saved_decks: Vec<Box<Path>>, // decklists whose cards are kept out of the trade list
+    #[serde(default)]
+    pub trade_min_value: f64, // cards worth less than this are left off the trade list
+    #[serde(default)]
+    pub trade_singleton: bool, // keep 1 copy of each card instead of a playset
+    #[serde(default)]
+    pub trade_format: TradeFormat,
+    #[serde(default)]
+    pub price_columns: bool, // show USD, EUR and Tix side by side on the Missing tab
+    #[serde(default)]
+    pub price_threshold: f64, // missing cards that cost more than this are highlighted, 0 is off
+    #[serde(default)]
+    pub deck_budget: f64, // money to spend on the missing cards of a deck, 0 is off
+    #[serde(default)]
+    pub target_format: Option<String>, // format substitutes have to be legal in, e.g. "pauper"
+    #[serde(default)]
+    pub palette: PaletteName, // colors used in the TUI
+    #[serde(default)]
+    pub missing_format: MissingFormat, // file format for the exported and copied missing list
+    #[serde(default)]
+    pub missing_set_codes: bool, // add the printing from the card database to the missing list
+    #[serde(default)]
+    pub report_format: ReportFormat, // file format for the shopping report
+    #[serde(default)]
+    pub keybindings: KeyBindings, // keep last, TOML tables have to come after the plain values
 }
 
 impl Default for DecklistConfig {
@@ -29,6 +58,19 @@ impl Default for DecklistConfig {
             database_num: 3,
             collection_path: None,
             currency: PriceType::USD,
+            saved_decks: Vec::new(),
+            trade_min_value: 0.0,
+            trade_singleton: false,
+            trade_format: TradeFormat::default(),
+            price_columns: false,
+            price_threshold: 0.0,
+            deck_budget: 0.0,
+            target_format: None,
+            palette: PaletteName::default(),
+            missing_format: MissingFormat::default(),
+            missing_set_codes: false,
+            report_format: ReportFormat::default(),
+            keybindings: KeyBindings::default(),
         }
     }
 }
@@ -0,0 +1,197 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::OpenOptions,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use csv::{ReaderBuilder, WriterBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::collection::CollectionCard;
+
+use super::scryfall::{make_safe_name, PriceType, ScryfallCard};
+
+/// file name of the price history stored in the database directory
+pub const HISTORY_FILE_NAME: &str = "price_history.csv";
+
+/// a single price snapshot for a card
+/// one of these is recorded for every card each time a new Scryfall file is loaded
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct PricePoint {
+    pub date: u64, // YYYYMMDD
+    pub name: String,
+    pub usd: Option<f64>,
+    pub eur: Option<f64>,
+    pub tix: Option<f64>,
+}
+
+impl PricePoint {
+    /// price of this snapshot in the requested currency
+    pub fn price(&self, currency: &PriceType) -> Option<f64> {
+        match currency {
+            PriceType::USD => self.usd,
+            PriceType::Euro => self.eur,
+            PriceType::Tix => self.tix,
+        }
+    }
+}
+
+/// price time series for every card, keyed by the same safe name as the card database
+/// each series is sorted oldest to newest
+#[derive(Clone, Default)]
+pub struct PriceHistory {
+    pub cards: HashMap<String, Vec<PricePoint>>,
+    pub dates: Vec<u64>,
+}
+
+impl PriceHistory {
+    /// adds a snapshot to the history, keeping the series sorted by date
+    fn insert(&mut self, point: PricePoint) {
+        if !self.dates.contains(&point.date) {
+            self.dates.push(point.date);
+            self.dates.sort();
+        }
+        let series = self.cards.entry(point.name.clone()).or_default();
+        series.push(point);
+        series.sort_by_key(|p| p.date);
+    }
+
+    /// returns the price series for a card name, if one exists
+    pub fn series(&self, name: &str) -> Option<&Vec<PricePoint>> {
+        self.cards.get(&make_safe_name(name, true))
+    }
+
+    /// percent change in price between the two most recent snapshots of a card
+    /// returns None if there isn't enough data to compare
+    pub fn trend(&self, name: &str, currency: &PriceType) -> Option<f64> {
+        let prices: Vec<f64> = self
+            .series(name)?
+            .iter()
+            .filter_map(|p| p.price(currency))
+            .collect();
+        if prices.len() < 2 {
+            return None;
+        }
+        let previous = prices[prices.len() - 2];
+        let latest = prices[prices.len() - 1];
+        if previous <= 0.0 {
+            return None;
+        }
+        Some((latest - previous) / previous * 100.0)
+    }
+
+    /// total value of a collection on every recorded date, oldest first
+    /// cards without a price on a given date are skipped for that date
+    pub fn collection_value(
+        &self,
+        collection: &[CollectionCard],
+        currency: &PriceType,
+    ) -> Vec<(u64, f64)> {
+        let mut totals: Vec<(u64, f64)> = self.dates.iter().map(|d| (*d, 0.0)).collect();
+        for card in collection {
+            if let Some(series) = self.series(&card.name) {
+                for point in series {
+                    if let Some(price) = point.price(currency) {
+                        if let Some(total) = totals.iter_mut().find(|(d, _)| *d == point.date) {
+                            total.1 += price * card.quantity as f64;
+                        }
+                    }
+                }
+            }
+        }
+        totals
+    }
+}
+
+/// takes the date from a Scryfall bulk file name (default-cards-YYYYMMDDHHMMSS.json)
+/// falls back to today's date for files that don't follow that pattern
+pub fn date_from_filename(filename: &str) -> u64 {
+    let sections: Vec<&str> = filename.split('-').collect();
+    if let Some(stamp) = sections.last() {
+        if stamp.len() >= 8 {
+            if let Ok(date) = stamp[..8].parse::<u64>() {
+                return date;
+            }
+        }
+    }
+    Local::now()
+        .format("%Y%m%d")
+        .to_string()
+        .parse::<u64>()
+        .unwrap_or(0)
+}
+
+/// reads the price history file from the database directory
+/// a missing file is not an error, it just means there is no history yet
+pub fn read_price_history(data_path: &Path) -> Result<PriceHistory, Box<dyn Error>> {
+    let mut history = PriceHistory::default();
+    let file_path = history_path(data_path);
+    if !file_path.exists() {
+        return Ok(history);
+    }
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(file_path)?;
+    for result in reader.deserialize() {
+        let point: PricePoint = result?;
+        history.insert(point);
+    }
+    Ok(history)
+}
+
+/// appends a price snapshot of every card in the database to the price history file
+/// nothing is written if a snapshot for this date was already recorded
+/// returns the updated history
+pub fn record_price_history(
+    data_path: &Path,
+    date: u64,
+    database: &HashMap<String, ScryfallCard>,
+) -> Result<PriceHistory, Box<dyn Error>> {
+    let mut history = read_price_history(data_path)?;
+    if history.dates.contains(&date) {
+        return Ok(history);
+    }
+    let file_path = history_path(data_path);
+    let new_file = !file_path.exists();
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)?;
+    let mut writer = WriterBuilder::new().has_headers(new_file).from_writer(file);
+    for (name, card) in database.iter() {
+        let point = PricePoint {
+            date,
+            name: name.clone(),
+            usd: parse_price(&card.prices.usd),
+            eur: parse_price(&card.prices.eur),
+            tix: parse_price(&card.prices.tix),
+        };
+        // cards without any price data would just bloat the file
+        if point.usd.is_none() && point.eur.is_none() && point.tix.is_none() {
+            continue;
+        }
+        writer.serialize(&point)?;
+        history.insert(point);
+    }
+    writer.flush()?;
+    Ok(history)
+}
+
+/// formats a percent change for display next to a price
+pub fn trend_fmt(change: Option<f64>) -> String {
+    match change {
+        Some(c) if c >= 0.05 => format!(" ▲ {:.1}%", c),
+        Some(c) if c <= -0.05 => format!(" ▼ {:.1}%", c.abs()),
+        _ => "".to_string(),
+    }
+}
+
+fn history_path(data_path: &Path) -> PathBuf {
+    data_path.join(HISTORY_FILE_NAME)
+}
+
+fn parse_price(price: &Option<String>) -> Option<f64> {
+    price.as_ref().and_then(|p| p.parse::<f64>().ok())
+}
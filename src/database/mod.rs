@@ -1 +1,3 @@
+pub mod history;
 pub mod scryfall;
+pub mod sets;
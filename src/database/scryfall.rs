@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     error::Error,
+    fmt::Display,
     fs::{self, File},
     io::Write,
     path::PathBuf,
@@ -10,6 +11,8 @@ use chrono::Local;
 use diacritics::remove_diacritics;
 use serde::{Deserialize, Serialize};
 
+use super::sets::{build_set_index, ScryfallSet};
+
 /// structure for all Scryfall card data for a unique card
 // TODO: map to JSON field names manually?  or rename?
 #[derive(Deserialize, Clone, Serialize)]
@@ -90,9 +93,71 @@ pub struct ScryfallCard {
     pub related_uris: ScryfallRelated,
     #[serde(default)]
     pub purchase_uris: ScryfallPurchase,
+    #[serde(default)]
+    pub lowest_prices: LowestPrices, // not from Scryfall, filled in when the bulk file is read
+    #[serde(default)]
+    pub all_games: Vec<GameFormat>, // games any printing is in, filled in like lowest_prices
+    #[serde(default)]
+    pub arena_rarity: Option<MtGRarity>, // lowest rarity of the Arena printings, filled in like lowest_prices
+    #[serde(default)]
+    pub card_faces: Vec<ScryfallCardFace>, // only for cards with more than one face
+}
+
+/// one face of a transform, modal double faced, flip, split or adventure card
+#[derive(Deserialize, Clone, Serialize)]
+pub struct ScryfallCardFace {
+    pub name: String,
+    #[serde(default)]
+    pub mana_cost: String,
+    pub type_line: Option<String>,
+    #[serde(default)]
+    pub oracle_text: String,
+    pub colors: Option<Vec<MtGColors>>,
 }
 
 impl ScryfallCard {
+    /// cheapest non-foil price of any printing, or this printing's price for databases saved
+    /// before every printing was checked
+    pub fn lowest_price(&self, currency: &PriceType) -> Option<f64> {
+        self.lowest_prices
+            .get(currency)
+            .or_else(|| self.prices.get(currency))
+    }
+
+    /// the card's colors, or its front face's for double faced cards that only have colors on
+    /// each face
+    pub fn face_colors(&self) -> Option<&Vec<MtGColors>> {
+        self.colors
+            .as_ref()
+            .or_else(|| self.card_faces.first().and_then(|f| f.colors.as_ref()))
+    }
+
+    /// the card's rules text, or the text of every face for double faced cards
+    pub fn face_oracle_text(&self) -> String {
+        if !self.oracle_text.is_empty() || self.card_faces.is_empty() {
+            return self.oracle_text.clone();
+        }
+        self.card_faces
+            .iter()
+            .map(|f| f.oracle_text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// the card's mana cost, or its front face's for double faced cards that only have a cost on
+    /// each face
+    pub fn face_mana_cost(&self) -> &str {
+        match self.card_faces.first() {
+            Some(face) if self.mana_cost.is_empty() => &face.mana_cost,
+            _ => &self.mana_cost,
+        }
+    }
+
+    /// the card can be played in a game with any of its printings
+    pub fn in_game(&self, game: &GameFormat) -> bool {
+        self.games.contains(game) || self.all_games.contains(game)
+    }
+
     pub fn price_to_string(self, quantity: u64, price_type: PriceType) -> String {
         let currency_str = match price_type {
             PriceType::USD => "$".to_string(),
@@ -274,7 +339,7 @@ pub enum CardTypes {
     Land,
 }
 
-#[derive(Deserialize, Clone, Serialize)]
+#[derive(Deserialize, Clone, Serialize, PartialEq)]
 pub enum MtGColors {
     #[serde(rename = "W")]
     White,
@@ -288,6 +353,19 @@ pub enum MtGColors {
     Green,
 }
 
+impl Display for MtGColors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MtGColors::White => "White",
+            MtGColors::Blue => "Blue",
+            MtGColors::Black => "Black",
+            MtGColors::Red => "Red",
+            MtGColors::Green => "Green",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 /// MtG card keywords
 #[derive(Deserialize, Clone, Serialize)]
 pub enum MtGKeyWords {
@@ -603,7 +681,7 @@ pub enum MtGKeyWords {
 }
 
 /// card legality options for a specific format
-#[derive(Deserialize, Clone, Serialize)]
+#[derive(Deserialize, Clone, Serialize, PartialEq)]
 pub enum Legality {
     #[serde(rename = "legal")]
     Legal,
@@ -669,8 +747,91 @@ impl Default for Legalities {
     }
 }
 
+/// Scryfall format names, in the same order as the fields of Legalities
+pub const FORMAT_NAMES: [&str; 21] = [
+    "standard",
+    "future",
+    "historic",
+    "timeless",
+    "gladiator",
+    "pioneer",
+    "modern",
+    "legacy",
+    "pauper",
+    "vintage",
+    "penny",
+    "commander",
+    "oathbreaker",
+    "standardbrawl",
+    "brawl",
+    "alchemy",
+    "paupercommander",
+    "duel",
+    "oldschool",
+    "premodern",
+    "predh",
+];
+
+/// display name for a format's Scryfall name
+pub fn format_label(format: &str) -> &str {
+    match format {
+        "standard" => "Standard",
+        "future" => "Future",
+        "historic" => "Historic",
+        "timeless" => "Timeless",
+        "gladiator" => "Gladiator",
+        "pioneer" => "Pioneer",
+        "modern" => "Modern",
+        "legacy" => "Legacy",
+        "pauper" => "Pauper",
+        "vintage" => "Vintage",
+        "penny" => "Penny",
+        "commander" => "Commander",
+        "oathbreaker" => "Oathbreaker",
+        "standardbrawl" => "Standard Brawl",
+        "brawl" => "Brawl",
+        "alchemy" => "Alchemy",
+        "paupercommander" => "Pauper Commander",
+        "duel" => "Duel",
+        "oldschool" => "Old School",
+        "premodern" => "Premodern",
+        "predh" => "Predh",
+        other => other,
+    }
+}
+
+impl Legalities {
+    /// looks up the legality for a format by its Scryfall name (see FORMAT_NAMES)
+    pub fn get(&self, format: &str) -> Option<&Legality> {
+        match format {
+            "standard" => Some(&self.standard),
+            "future" => Some(&self.future),
+            "historic" => Some(&self.historic),
+            "timeless" => Some(&self.timeless),
+            "gladiator" => Some(&self.gladiator),
+            "pioneer" => Some(&self.pioneer),
+            "modern" => Some(&self.modern),
+            "legacy" => Some(&self.legacy),
+            "pauper" => Some(&self.pauper),
+            "vintage" => Some(&self.vintage),
+            "penny" => Some(&self.penny),
+            "commander" => Some(&self.commander),
+            "oathbreaker" => Some(&self.oathbreaker),
+            "standardbrawl" => Some(&self.standardbrawl),
+            "brawl" => Some(&self.brawl),
+            "alchemy" => Some(&self.alchemy),
+            "paupercommander" => Some(&self.paupercommander),
+            "duel" => Some(&self.duel),
+            "oldschool" => Some(&self.oldschool),
+            "premodern" => Some(&self.premodern),
+            "predh" => Some(&self.predh),
+            _ => None,
+        }
+    }
+}
+
 /// different game formats
-#[derive(Deserialize, Clone, Serialize)]
+#[derive(Deserialize, Clone, Copy, Serialize, Debug, PartialEq)]
 pub enum GameFormat {
     #[serde(rename = "paper")]
     Paper,
@@ -749,7 +910,7 @@ pub enum ScryfallSetType {
 }
 
 /// card rarities
-#[derive(Deserialize, Clone, Serialize)]
+#[derive(Deserialize, Clone, Serialize, PartialEq)]
 pub enum MtGRarity {
     #[serde(rename = "common")]
     Common,
@@ -765,6 +926,34 @@ pub enum MtGRarity {
     Bonus,
 }
 
+impl MtGRarity {
+    /// common is lowest, special and bonus are above mythic
+    pub fn rank(&self) -> u8 {
+        match self {
+            MtGRarity::Common => 0,
+            MtGRarity::Uncommon => 1,
+            MtGRarity::Rare => 2,
+            MtGRarity::Mythic => 3,
+            MtGRarity::Special => 4,
+            MtGRarity::Bonus => 5,
+        }
+    }
+}
+
+impl Display for MtGRarity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MtGRarity::Common => "Common",
+            MtGRarity::Uncommon => "Uncommon",
+            MtGRarity::Rare => "Rare",
+            MtGRarity::Mythic => "Mythic",
+            MtGRarity::Special => "Special",
+            MtGRarity::Bonus => "Bonus",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 /// card border colors
 #[derive(Deserialize, Clone, Serialize)]
 pub enum BorderColor {
@@ -793,6 +982,63 @@ pub struct ScryfallPrices {
     pub tix: Option<String>,        // Option<f64>,
 }
 
+impl ScryfallPrices {
+    /// non-foil price in the given currency, if Scryfall has one
+    pub fn get(&self, currency: &PriceType) -> Option<f64> {
+        let price_str = match currency {
+            PriceType::USD => self.usd.as_ref(),
+            PriceType::Euro => self.eur.as_ref(),
+            PriceType::Tix => self.tix.as_ref(),
+        }?;
+        price_str.parse::<f64>().ok()
+    }
+
+    /// price of a finish the way Moxfield names it, "foil", "etched" or blank for non-foil
+    /// finishes without a price of their own fall back to the non-foil price
+    pub fn get_finish(&self, currency: &PriceType, finish: &str) -> Option<f64> {
+        let price_str = match (currency, finish) {
+            (PriceType::USD, "foil") => self.usd_foil.as_ref(),
+            (PriceType::USD, "etched") => self.usd_etched.as_ref(),
+            (PriceType::Euro, "foil" | "etched") => self.eur_foil.as_ref(),
+            _ => None,
+        };
+        price_str
+            .and_then(|p| p.parse::<f64>().ok())
+            .or_else(|| self.get(currency))
+    }
+}
+
+/// cheapest non-foil price of any printing of a card in every currency, so the currency can be
+/// switched without reading the bulk file again
+#[derive(Deserialize, Clone, Serialize, Default, Debug)]
+pub struct LowestPrices {
+    pub usd: Option<f64>,
+    pub eur: Option<f64>,
+    pub tix: Option<f64>,
+}
+
+impl LowestPrices {
+    pub fn get(&self, currency: &PriceType) -> Option<f64> {
+        match currency {
+            PriceType::USD => self.usd,
+            PriceType::Euro => self.eur,
+            PriceType::Tix => self.tix,
+        }
+    }
+
+    /// keeps the lower of the current prices and another printing's prices
+    fn add(&mut self, prices: &ScryfallPrices) {
+        let lower = |current: Option<f64>, other: Option<f64>| match (current, other) {
+            (Some(c), Some(o)) => Some(c.min(o)),
+            (c, o) => c.or(o),
+        };
+        let positive = |p: Option<f64>| p.filter(|p| *p > 0.0);
+        self.usd = lower(self.usd, positive(prices.get(&PriceType::USD)));
+        self.eur = lower(self.eur, positive(prices.get(&PriceType::Euro)));
+        self.tix = lower(self.tix, positive(prices.get(&PriceType::Tix)));
+    }
+}
+
 /// selected currency to show prices in
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub enum PriceType {
@@ -801,6 +1047,25 @@ pub enum PriceType {
     Tix,
 }
 
+impl PriceType {
+    pub fn next(&self) -> Self {
+        match self {
+            PriceType::USD => PriceType::Euro,
+            PriceType::Euro => PriceType::Tix,
+            PriceType::Tix => PriceType::USD,
+        }
+    }
+
+    /// symbol printed in front of prices
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            PriceType::USD => "$",
+            PriceType::Euro => "€",
+            PriceType::Tix => "Tix ",
+        }
+    }
+}
+
 /// struct of all of Scryfall's related URIs
 #[derive(Deserialize, Clone, Serialize)]
 pub struct ScryfallRelated {
@@ -825,22 +1090,28 @@ pub struct ScryfallPurchase {
     pub cardhoarder: String,
 }
 
+/// cards keyed by safe name along with every printing grouped by set
+pub type ScryfallDatabase = (HashMap<String, ScryfallCard>, HashMap<String, ScryfallSet>);
+
 /// reads provided JSON database file and produces a hash map of ScryfallCard objects
+/// also returns every printing grouped by set
 pub fn read_scryfall_database(
     path: &PathBuf,
     currency: PriceType,
-) -> Result<HashMap<String, ScryfallCard>, Box<dyn Error>> {
+) -> Result<ScryfallDatabase, Box<dyn Error>> {
     let file_text = fs::read_to_string(path)?;
     let test: Result<Vec<ScryfallCard>, serde_json::Error> = serde_json::from_str(&file_text);
     let card_vec = test?;
     let mut result_map: HashMap<String, ScryfallCard> = HashMap::new();
-    for card in &card_vec {
-        let dual = card.layout == CardLayouts::Transform
+    let dual = |card: &ScryfallCard| {
+        card.layout == CardLayouts::Transform
             || card.layout == CardLayouts::Flip
             || card.layout == CardLayouts::Split
             || card.layout == CardLayouts::ModalDualFaceCard
-            || card.layout == CardLayouts::Adventure;
-        let safe_name = make_safe_name(&card.name, dual);
+            || card.layout == CardLayouts::Adventure
+    };
+    for card in &card_vec {
+        let safe_name = make_safe_name(&card.name, dual(card));
         result_map
             .entry(safe_name)
             .and_modify(|existing| {
@@ -869,7 +1140,27 @@ pub fn read_scryfall_database(
             })
             .or_insert(card.clone());
     }
-    Ok(result_map)
+    // the printing kept above is the cheapest in the config currency, the others are checked
+    // here so switching currency still shows the cheapest price
+    for card in &card_vec {
+        if let Some(kept) = result_map.get_mut(&make_safe_name(&card.name, dual(card))) {
+            kept.lowest_prices.add(&card.prices);
+            for game in &card.games {
+                if !kept.all_games.contains(game) {
+                    kept.all_games.push(*game);
+                }
+            }
+            if card.games.contains(&GameFormat::Arena)
+                && kept
+                    .arena_rarity
+                    .as_ref()
+                    .is_none_or(|r| card.rarity.rank() < r.rank())
+            {
+                kept.arena_rarity = Some(card.rarity.clone());
+            }
+        }
+    }
+    Ok((result_map, build_set_index(&card_vec)))
 }
 
 /// reads custom decklist JSON file
@@ -895,12 +1186,7 @@ pub fn match_card(
 pub fn get_min_price(cards: &[ScryfallCard], currency: PriceType) -> f64 {
     let mut price = 0.0;
     for card in cards.iter() {
-        if let Some(price_str) = match currency {
-            PriceType::USD => card.prices.usd.clone(),
-            PriceType::Euro => card.prices.eur.clone(),
-            PriceType::Tix => card.prices.tix.clone(),
-        } {
-            let price_float = price_str.parse::<f64>().unwrap_or(0.0);
+        if let Some(price_float) = card.lowest_price(&currency) {
             if price == 0.0 && price_float > 0.0 {
                 price = price_float;
             } else if price_float > 0.0 && price_float < price {
@@ -953,3 +1239,33 @@ pub async fn serialize_database(
     file.write_all(json_string.as_bytes())?;
     Ok(())
 }
+
+#[cfg(test)]
+impl ScryfallCard {
+    /// a paper card with the given name, type line, mana cost and rules text for unit tests
+    pub fn test_card(name: &str, type_line: &str, mana_cost: &str, oracle_text: &str) -> Self {
+        let mut card: serde_json::Value = serde_json::from_str(
+            r#"{"object": "card", "id": "", "oracle_id": "", "multiverse_ids": [], "name": "",
+            "lang": "en", "released_at": "2020-01-01", "uri": "", "scryfall_uri": "",
+            "layout": "normal", "highres_image": true, "image_status": "highres_scan",
+            "cmc": 0.0, "colors": [], "color_identity": [], "keywords": [], "games": ["paper"],
+            "reserved": false, "foil": false, "nonfoil": true, "finishes": ["nonfoil"],
+            "oversized": false, "promo": false, "reprint": false, "variation": false,
+            "set_id": "", "set": "tst", "set_name": "Test", "set_type": "expansion",
+            "set_uri": "", "set_search_uri": "", "prints_search_uri": "",
+            "collector_number": "1", "digital": false, "rarity": "common",
+            "border_color": "black", "frame": "2015", "full_art": false, "textless": false,
+            "booster": true, "story_spotlight": false, "related_uris": {},
+            "prices": {"usd": null, "usd_foil": null, "usd_etched": null, "eur": null,
+            "eur_foil": null, "tix": null}}"#,
+        )
+        .unwrap();
+        card["legalities"] = serde_json::to_value(Legalities::default()).unwrap();
+        let mut card: ScryfallCard = serde_json::from_value(card).unwrap();
+        card.name = name.to_string();
+        card.type_line = Some(type_line.to_string());
+        card.mana_cost = mana_cost.to_string();
+        card.oracle_text = oracle_text.to_string();
+        card
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use super::scryfall::{CardLayouts, MtGRarity, ScryfallCard, ScryfallPrices, ScryfallSetType};
+
+/// a single printing of a card within a set
+/// only the fields needed to track set completion are kept, the full card data is too big
+#[derive(Deserialize, Serialize, Clone)]
+pub struct SetPrinting {
+    pub name: String,
+    pub collector_number: String,
+    pub rarity: MtGRarity,
+    pub prices: ScryfallPrices,
+}
+
+/// every printing in a set, used to check how much of a set is in the collection
+#[derive(Deserialize, Serialize, Clone)]
+pub struct ScryfallSet {
+    pub code: String,
+    pub name: String,
+    pub set_type: ScryfallSetType,
+    pub released_at: String,
+    pub printings: Vec<SetPrinting>,
+}
+
+/// groups every printing in the Scryfall bulk data by set
+/// tokens, emblems and art cards aren't part of completing a set, so they are skipped
+pub fn build_set_index(cards: &[ScryfallCard]) -> HashMap<String, ScryfallSet> {
+    let mut sets: HashMap<String, ScryfallSet> = HashMap::new();
+    for card in cards {
+        if card.layout == CardLayouts::Token
+            || card.layout == CardLayouts::DoubleFacedToken
+            || card.layout == CardLayouts::Emblem
+            || card.layout == CardLayouts::ArtSeries
+        {
+            continue;
+        }
+        let set = sets.entry(card.set.clone()).or_insert(ScryfallSet {
+            code: card.set.clone(),
+            name: card.set_name.clone(),
+            set_type: card.set_type.clone(),
+            released_at: card.released_at.clone(),
+            printings: Vec::new(),
+        });
+        set.printings.push(SetPrinting {
+            name: card.name.clone(),
+            collector_number: card.collector_number.clone(),
+            rarity: card.rarity.clone(),
+            prices: card.prices.clone(),
+        });
+    }
+    sets
+}
+
+/// the exact printing of a collection line, found by set code and collector number
+/// None when the collection file doesn't have them or the printing isn't in the set index
+pub fn owned_printing<'a>(
+    edition: &str,
+    collector_number: &str,
+    sets: &'a HashMap<String, ScryfallSet>,
+) -> Option<&'a SetPrinting> {
+    if collector_number.is_empty() {
+        return None;
+    }
+    sets.get(&edition.to_lowercase())?
+        .printings
+        .iter()
+        .find(|p| p.collector_number == collector_number)
+}
+
+/// name of the set index file that goes with a decklist database file
+/// decklist_YYYYMMDD.json -> sets_YYYYMMDD.json
+pub fn sets_file_name(decklist_file_name: &str) -> String {
+    decklist_file_name.replacen("decklist", "sets", 1)
+}
+
+/// saves the set index next to the compact decklist database so it doesn't have to be rebuilt
+/// from the Scryfall file every time
+pub async fn serialize_sets(
+    sets: &HashMap<String, ScryfallSet>,
+    path: PathBuf,
+) -> Result<(), Box<dyn Error>> {
+    let json_string = serde_json::to_string(sets)?;
+    let date = Local::now();
+    let file_path = path.join(format!("sets_{}.json", date.format("%Y%m%d")));
+    let mut file = File::create(file_path)?;
+    file.write_all(json_string.as_bytes())?;
+    Ok(())
+}
+
+/// reads a set index saved by serialize_sets()
+pub fn read_sets_database(path: &Path) -> Result<HashMap<String, ScryfallSet>, Box<dyn Error>> {
+    let file_text = fs::read_to_string(path)?;
+    let sets: HashMap<String, ScryfallSet> = serde_json::from_str(&file_text)?;
+    Ok(sets)
+}
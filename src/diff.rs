@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use crate::{
+    collection::{CollectionCard, DeckSection},
+    database::scryfall::{get_min_price, make_safe_name, PriceType, ScryfallCard},
+};
+
+/// a card that was added, removed or changed quantity in one section of the deck
+#[derive(Clone, Debug)]
+pub struct DeckChange {
+    pub name: String,
+    pub section: DeckSection,
+    pub old: u64,
+    pub new: u64,
+}
+
+impl DeckChange {
+    /// + for added cards, - for removed cards, ~ for changed quantities
+    pub fn symbol(&self) -> &'static str {
+        if self.old == 0 {
+            "+"
+        } else if self.new == 0 {
+            "-"
+        } else {
+            "~"
+        }
+    }
+}
+
+/// copies the new version of the deck needs on top of the old version
+#[derive(Clone, Debug)]
+pub struct DiffNeed {
+    pub name: String,
+    pub added: u64, // extra copies compared to the old version, main and sideboard together
+    pub owned: u64, // copies in the collection
+    pub to_buy: u64,
+    pub unit_price: Option<f64>, // None if the card isn't in the database
+}
+
+/// differences between two versions of a decklist
+#[derive(Clone, Debug, Default)]
+pub struct DeckDiff {
+    pub changes: Vec<DeckChange>, // grouped by section
+    pub needs: Vec<DiffNeed>,
+}
+
+impl DeckDiff {
+    /// cost of every card that has to be bought, cards without a price count as 0
+    pub fn total_cost(&self) -> f64 {
+        self.needs
+            .iter()
+            .map(|n| n.unit_price.unwrap_or(0.0) * n.to_buy as f64)
+            .sum()
+    }
+}
+
+/// compares the old and new version of a decklist
+/// copies in the old version count as owned, so the cards to buy are the ones the new version
+/// needs beyond both the old version and the collection
+pub fn deck_diff(
+    old: &[CollectionCard],
+    new: &[CollectionCard],
+    collection: &[CollectionCard],
+    database: &HashMap<String, ScryfallCard>,
+    currency: &PriceType,
+) -> DeckDiff {
+    // quantities by section and card, keeping the order of the new version
+    let mut changes: Vec<DeckChange> = Vec::new();
+    let mut change_index: HashMap<(DeckSection, String), usize> = HashMap::new();
+    let mut add = |card: &CollectionCard, is_new: bool| {
+        let key = (card.section, make_safe_name(&card.name, true));
+        let i = *change_index.entry(key).or_insert_with(|| {
+            changes.push(DeckChange {
+                name: card.name.clone(),
+                section: card.section,
+                old: 0,
+                new: 0,
+            });
+            changes.len() - 1
+        });
+        if is_new {
+            changes[i].new += card.quantity;
+        } else {
+            changes[i].old += card.quantity;
+        }
+    };
+    for card in new {
+        add(card, true);
+    }
+    for card in old {
+        add(card, false);
+    }
+    let mut owned: HashMap<String, u64> = HashMap::new();
+    for card in collection {
+        *owned.entry(make_safe_name(&card.name, true)).or_insert(0) += card.quantity;
+    }
+    // totals across sections, a card moved from the sideboard to the main deck isn't needed
+    let mut totals: Vec<(String, String, u64, u64)> = Vec::new();
+    for change in changes.iter() {
+        let safe_name = make_safe_name(&change.name, true);
+        match totals.iter_mut().find(|t| t.0 == safe_name) {
+            Some(t) => {
+                t.2 += change.old;
+                t.3 += change.new;
+            }
+            None => totals.push((safe_name, change.name.clone(), change.old, change.new)),
+        }
+    }
+    changes.retain(|c| c.old != c.new);
+    changes.sort_by_key(|c| c.section);
+    let mut needs = Vec::new();
+    for (safe_name, name, old_total, new_total) in totals {
+        if new_total <= old_total {
+            continue;
+        }
+        let owned = *owned.get(&safe_name).unwrap_or(&0);
+        let unit_price = database
+            .get(&safe_name)
+            .map(|m| get_min_price(std::slice::from_ref(m), currency.clone()));
+        needs.push(DiffNeed {
+            name,
+            added: new_total - old_total,
+            owned,
+            to_buy: new_total.saturating_sub(old_total.max(owned)),
+            unit_price,
+        });
+    }
+    DeckDiff { changes, needs }
+}
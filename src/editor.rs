@@ -0,0 +1,113 @@
+use crate::{
+    collection::{CollectionCard, DeckLabels, DeckSection},
+    database::scryfall::make_safe_name,
+};
+
+/// changes the quantity of a decklist card, removing it when it gets to 0
+pub fn change_quantity(decklist: &mut Vec<CollectionCard>, index: usize, delta: i64) {
+    let card = match decklist.get_mut(index) {
+        Some(c) => c,
+        None => return,
+    };
+    card.quantity = card.quantity.saturating_add_signed(delta);
+    if card.quantity == 0 {
+        decklist.remove(index);
+    }
+}
+
+/// adds copies of a card to a section, returning its position in the decklist
+/// cards already in the section get more copies, new cards go at the end of the section
+pub fn add_card(
+    decklist: &mut Vec<CollectionCard>,
+    name: &str,
+    quantity: u64,
+    section: DeckSection,
+) -> usize {
+    let safe_name = make_safe_name(name, true).to_lowercase();
+    if let Some(i) = decklist.iter().position(|c| {
+        c.section == section && make_safe_name(&c.name, true).to_lowercase() == safe_name
+    }) {
+        decklist[i].quantity += quantity;
+        return i;
+    }
+    // the decklist is kept in commander, main deck, sideboard order
+    let i = decklist
+        .iter()
+        .position(|c| c.section > section)
+        .unwrap_or(decklist.len());
+    decklist.insert(
+        i,
+        CollectionCard {
+            name: name.to_string(),
+            quantity,
+            section,
+            ..Default::default()
+        },
+    );
+    i
+}
+
+/// moves a card between the main deck and the sideboard, returning its new position
+/// commanders move to the main deck
+pub fn move_card(decklist: &mut Vec<CollectionCard>, index: usize) -> usize {
+    if index >= decklist.len() {
+        return index;
+    }
+    let card = decklist.remove(index);
+    let section = match card.section {
+        DeckSection::Main => DeckSection::Sideboard,
+        DeckSection::Sideboard | DeckSection::Commander => DeckSection::Main,
+    };
+    add_card(decklist, &card.name, card.quantity, section)
+}
+
+/// the decklist in the format read_decklist() reads, with the sideboard after the main deck
+/// uses the section labels and commander marker the file was read with, if it had them
+pub fn decklist_string(
+    decklist: &[CollectionCard],
+    deck_labels: &DeckLabels,
+    line_ending: &str,
+) -> String {
+    let mut deck_string = String::new();
+    // the main deck only needs a label when the file had one
+    let mut section = None;
+    for card in decklist {
+        if card.section == DeckSection::Commander {
+            if let Some(marker) = &deck_labels.commander_marker {
+                deck_string += &format!("{} {}{}", card, marker, line_ending);
+                continue;
+            }
+        }
+        if section != Some(card.section) {
+            let label = deck_labels.labels.get(&card.section);
+            if section.is_some() || card.section != DeckSection::Main || label.is_some() {
+                let label = label.cloned().unwrap_or_else(|| card.section.to_string());
+                deck_string += &format!("{}{}", label, line_ending);
+            }
+            section = Some(card.section);
+        }
+        deck_string += &format!("{}{}", card, line_ending);
+    }
+    deck_string
+}
+
+/// renames a card, for fixing misspelled names, returning its new position
+/// if the section already has a card with the new name the copies are combined
+pub fn rename_card(decklist: &mut Vec<CollectionCard>, index: usize, name: &str) -> usize {
+    let section = match decklist.get(index) {
+        Some(c) => c.section,
+        None => return index,
+    };
+    let safe_name = make_safe_name(name, true).to_lowercase();
+    let duplicate = decklist.iter().enumerate().any(|(i, c)| {
+        i != index
+            && c.section == section
+            && make_safe_name(&c.name, true).to_lowercase() == safe_name
+    });
+    if duplicate {
+        let card = decklist.remove(index);
+        return add_card(decklist, name, card.quantity, section);
+    }
+    decklist[index].name = name.to_string();
+    index
+}
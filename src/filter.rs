@@ -0,0 +1,159 @@
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    collection::CollectionCard,
+    database::scryfall::{make_safe_name, PriceType, ScryfallCard},
+    search::{normalize, parse_number, Comparison},
+};
+
+/// the list filters that are supported, shown when a filter can't be parsed
+pub const FILTER_HELP: &str = "name text, price>N, qty>=N, !db (not in database)";
+
+/// a single condition used to find cards in the Collection, Deck and Missing lists
+#[derive(Debug, Clone)]
+pub enum ListFilter {
+    Name(String),
+    Price(Comparison, f64),
+    Quantity(Comparison, f64),
+    NotInDatabase,
+}
+
+/// the text typed after / on a list, every filter has to match
+#[derive(Debug, Clone, Default)]
+pub struct ListQuery {
+    pub filters: Vec<ListFilter>,
+}
+
+impl FromStr for ListQuery {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filters = Vec::new();
+        for word in s.split_whitespace() {
+            let lower = word.to_lowercase();
+            let filter = if lower == "!db" || lower == "notfound" {
+                ListFilter::NotInDatabase
+            } else if let Some(rest) = lower.strip_prefix("price") {
+                match Comparison::split(rest) {
+                    Some((comparison, value)) => {
+                        ListFilter::Price(comparison, parse_number(value)?)
+                    }
+                    None => ListFilter::Name(normalize(word)),
+                }
+            } else if let Some(rest) = lower.strip_prefix("qty") {
+                match Comparison::split(rest) {
+                    Some((comparison, value)) => {
+                        ListFilter::Quantity(comparison, parse_number(value)?)
+                    }
+                    None => ListFilter::Name(normalize(word)),
+                }
+            } else {
+                ListFilter::Name(normalize(word))
+            };
+            filters.push(filter);
+        }
+        Ok(ListQuery { filters })
+    }
+}
+
+impl ListQuery {
+    /// checks a card from one of the lists against every filter
+    /// price filters use the configured currency and never match cards without a price
+    pub fn matches(
+        &self,
+        card: &CollectionCard,
+        database: &HashMap<String, ScryfallCard>,
+        currency: &PriceType,
+    ) -> bool {
+        let name = normalize(&card.name);
+        let matched = database.get(&make_safe_name(&card.name, true));
+        self.filters.iter().all(|filter| match filter {
+            ListFilter::Name(text) => name.contains(text.as_str()),
+            ListFilter::Price(comparison, price) => matched
+                .and_then(|c| c.prices.get(currency))
+                .is_some_and(|p| comparison.compare(p, *price)),
+            ListFilter::Quantity(comparison, quantity) => {
+                comparison.compare(card.quantity as f64, *quantity)
+            }
+            // without a database there is nothing to compare to
+            ListFilter::NotInDatabase => !database.is_empty() && matched.is_none(),
+        })
+    }
+}
+
+/// indices of every card in the list that matches the query
+pub fn find_matches(
+    cards: &[CollectionCard],
+    query: &ListQuery,
+    database: &HashMap<String, ScryfallCard>,
+    currency: &PriceType,
+) -> Vec<usize> {
+    if query.filters.is_empty() {
+        return Vec::new();
+    }
+    cards
+        .iter()
+        .enumerate()
+        .filter(|(_, card)| query.matches(card, database, currency))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(name: &str, quantity: u64) -> CollectionCard {
+        CollectionCard {
+            name: name.to_string(),
+            quantity,
+            ..Default::default()
+        }
+    }
+
+    fn database() -> HashMap<String, ScryfallCard> {
+        let mut bolt = ScryfallCard::test_card("Lightning Bolt", "Instant", "{R}", "");
+        bolt.prices.usd = Some("2.00".to_string());
+        let island = ScryfallCard::test_card("Island", "Basic Land — Island", "", "");
+        [bolt, island]
+            .into_iter()
+            .map(|c| (make_safe_name(&c.name, true), c))
+            .collect()
+    }
+
+    #[test]
+    fn parses_filters() {
+        let query: ListQuery = "Bolt price>=1.5 qty<4 !db price".parse().unwrap();
+        assert!(matches!(&query.filters[0], ListFilter::Name(n) if n == "bolt"));
+        assert!(
+            matches!(query.filters[1], ListFilter::Price(Comparison::GreaterEq, p) if p == 1.5)
+        );
+        assert!(matches!(query.filters[2], ListFilter::Quantity(Comparison::Less, q) if q == 4.0));
+        assert!(matches!(query.filters[3], ListFilter::NotInDatabase));
+        assert!(matches!(&query.filters[4], ListFilter::Name(n) if n == "price"));
+        assert!("price<abc".parse::<ListQuery>().is_err());
+    }
+
+    #[test]
+    fn finds_matching_cards() {
+        let cards = [
+            card("Lightning Bolt", 4),
+            card("Island", 20),
+            card("Misspelled Card", 1),
+        ];
+        let database = database();
+        let find = |text: &str| {
+            let query: ListQuery = text.parse().unwrap();
+            find_matches(&cards, &query, &database, &PriceType::USD)
+        };
+        assert_eq!(find("bolt"), vec![0]);
+        assert_eq!(find("price>1"), vec![0]);
+        assert_eq!(find("qty>=4"), vec![0, 1]);
+        assert_eq!(find("!db"), vec![2]);
+        assert_eq!(find("is qty>10"), vec![1]);
+        assert!(find("").is_empty());
+        // without a database no card counts as missing from it
+        let query: ListQuery = "!db".parse().unwrap();
+        assert!(find_matches(&cards, &query, &HashMap::new(), &PriceType::USD).is_empty());
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+
+/// things a key can do, outside of typing text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    TabWelcome,
+    TabDatabase,
+    TabCollection,
+    TabDeck,
+    TabMissing,
+    TabHelp,
+    TabValue,
+    TabSets,
+    TabSearch,
+    TabDebug,
+    Quit,
+    Copy,
+    Load,
+    SaveFile,
+    Toggle,
+    Rarity,
+    ListSearch,
+    Sort,
+    SampleHand,
+    Compare,
+    Edit,
+    Report,
+    Currency,
+    PriceColumns,
+    Wildcards,
+    NextMatch,
+    PreviousMatch,
+    Select,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Back,
+}
+
+impl Action {
+    /// every action, in the order they are listed on the Help tab
+    pub const ALL: [Action; 37] = [
+        Action::TabWelcome,
+        Action::TabDatabase,
+        Action::TabCollection,
+        Action::TabDeck,
+        Action::TabMissing,
+        Action::TabHelp,
+        Action::TabValue,
+        Action::TabSets,
+        Action::TabSearch,
+        Action::TabDebug,
+        Action::Quit,
+        Action::Copy,
+        Action::Load,
+        Action::SaveFile,
+        Action::Toggle,
+        Action::Rarity,
+        Action::ListSearch,
+        Action::Sort,
+        Action::SampleHand,
+        Action::Compare,
+        Action::Edit,
+        Action::Report,
+        Action::Currency,
+        Action::PriceColumns,
+        Action::Wildcards,
+        Action::NextMatch,
+        Action::PreviousMatch,
+        Action::Select,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Home,
+        Action::End,
+        Action::Back,
+    ];
+
+    /// name used in the [keybindings] section of config.toml
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::TabWelcome => "tab_welcome",
+            Action::TabDatabase => "tab_database",
+            Action::TabCollection => "tab_collection",
+            Action::TabDeck => "tab_deck",
+            Action::TabMissing => "tab_missing",
+            Action::TabHelp => "tab_help",
+            Action::TabValue => "tab_value",
+            Action::TabSets => "tab_sets",
+            Action::TabSearch => "tab_search",
+            Action::TabDebug => "tab_debug",
+            Action::Quit => "quit",
+            Action::Copy => "copy",
+            Action::Load => "load",
+            Action::SaveFile => "save_file",
+            Action::Toggle => "toggle",
+            Action::Rarity => "rarity",
+            Action::ListSearch => "list_search",
+            Action::Sort => "sort",
+            Action::SampleHand => "sample_hand",
+            Action::Compare => "compare",
+            Action::Edit => "edit",
+            Action::Report => "report",
+            Action::Currency => "currency",
+            Action::PriceColumns => "price_columns",
+            Action::Wildcards => "wildcards",
+            Action::NextMatch => "next_match",
+            Action::PreviousMatch => "previous_match",
+            Action::Select => "select",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Home => "home",
+            Action::End => "end",
+            Action::Back => "back",
+        }
+    }
+
+    /// what the action does, for the Help tab
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::TabWelcome => "Welcome tab",
+            Action::TabDatabase => "Database tab",
+            Action::TabCollection => "Collection tab",
+            Action::TabDeck => "Deck tab",
+            Action::TabMissing => "Missing tab",
+            Action::TabHelp => "Help tab",
+            Action::TabValue => "Value tab",
+            Action::TabSets => "Sets tab",
+            Action::TabSearch => "Search tab",
+            Action::TabDebug => "Debug tab",
+            Action::Quit => "Quit",
+            Action::Copy => "Copy to clipboard / save config",
+            Action::Load => "Load file",
+            Action::SaveFile => "Save to file",
+            Action::Toggle => "Toggle view",
+            Action::Rarity => "Change rarity",
+            Action::ListSearch => "Search list",
+            Action::Sort => "Sort missing cards",
+            Action::SampleHand => "Sample hand",
+            Action::Compare => "Compare deck versions",
+            Action::Edit => "Edit deck",
+            Action::Report => "Save shopping report",
+            Action::Currency => "Switch currency",
+            Action::PriceColumns => "USD/EUR/Tix columns",
+            Action::Wildcards => "Arena wildcards",
+            Action::NextMatch => "Next match",
+            Action::PreviousMatch => "Previous match",
+            Action::Select => "Select / start typing",
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Left => "Left",
+            Action::Right => "Right",
+            Action::PageUp => "Page up",
+            Action::PageDown => "Page down",
+            Action::Home => "First row",
+            Action::End => "Last row",
+            Action::Back => "Back / reset",
+        }
+    }
+
+    /// keys used when config.toml doesn't set the action
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::TabWelcome => &["1"],
+            Action::TabDatabase => &["2"],
+            Action::TabCollection => &["3"],
+            Action::TabDeck => &["4"],
+            Action::TabMissing => &["5"],
+            Action::TabHelp => &["6"],
+            Action::TabValue => &["7"],
+            Action::TabSets => &["8"],
+            Action::TabSearch => &["9"],
+            Action::TabDebug => &["0"],
+            Action::Quit => &["q"],
+            Action::Copy => &["c"],
+            Action::Load => &["s"],
+            Action::SaveFile => &["f"],
+            Action::Toggle => &["t"],
+            Action::Rarity => &["r"],
+            Action::ListSearch => &["/"],
+            Action::Sort => &["o"],
+            Action::SampleHand => &["h"],
+            Action::Compare => &["d"],
+            Action::Edit => &["e"],
+            Action::Report => &["p"],
+            Action::Currency => &["$"],
+            Action::PriceColumns => &["v"],
+            Action::Wildcards => &["w"],
+            Action::NextMatch => &["n"],
+            Action::PreviousMatch => &["N"],
+            Action::Select => &["Enter"],
+            Action::Up => &["Up", "k"],
+            Action::Down => &["Down", "j"],
+            Action::Left => &["Left"],
+            Action::Right => &["Right"],
+            Action::PageUp => &["PageUp"],
+            Action::PageDown => &["PageDown"],
+            Action::Home => &["Home"],
+            Action::End => &["End"],
+            Action::Back => &["Esc"],
+        }
+    }
+}
+
+/// the [keybindings] section of config.toml, action names mapped to lists of keys
+/// actions that aren't listed keep their default keys
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct KeyBindings(pub BTreeMap<String, Vec<String>>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings(
+            Action::ALL
+                .iter()
+                .map(|a| {
+                    let keys = a.default_keys().iter().map(|k| k.to_string()).collect();
+                    (a.name().to_string(), keys)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl KeyBindings {
+    /// the keys bound to an action, as written in the config
+    pub fn keys(&self, action: Action) -> Vec<String> {
+        match self.0.get(action.name()) {
+            Some(keys) => keys.clone(),
+            None => action
+                .default_keys()
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+        }
+    }
+
+    /// the action is set in the config to something other than its default keys
+    fn is_explicit(&self, action: Action) -> bool {
+        self.0.get(action.name()).is_some_and(|keys| {
+            !keys
+                .iter()
+                .map(|k| k.as_str())
+                .eq(action.default_keys().iter().copied())
+        })
+    }
+
+    /// the action for every bound key, and any problems with the bindings
+    /// default keys go in first so keys set in the config take them over, only two keys set in
+    /// the config are a conflict
+    pub fn key_map(&self) -> (HashMap<KeyCode, Action>, Vec<String>) {
+        let mut errors = Vec::new();
+        for name in self.0.keys() {
+            if !Action::ALL.iter().any(|a| a.name() == name) {
+                errors.push(format!("Unknown key binding action: {}", name));
+            }
+        }
+        let mut key_map: HashMap<KeyCode, Action> = HashMap::new();
+        let mut explicit_keys: HashMap<KeyCode, Action> = HashMap::new();
+        for explicit in [false, true] {
+            for action in Action::ALL {
+                if self.is_explicit(action) != explicit {
+                    continue;
+                }
+                for key in self.keys(action) {
+                    let code = match parse_key(&key) {
+                        Ok(c) => c,
+                        Err(e) => {
+                            errors.push(e);
+                            continue;
+                        }
+                    };
+                    key_map.insert(code, action);
+                    if !explicit {
+                        continue;
+                    }
+                    if let Some(other) = explicit_keys.insert(code, action) {
+                        if other != action {
+                            errors.push(format!(
+                                "{} is bound to both {} and {}, using {}",
+                                key,
+                                other.name(),
+                                action.name(),
+                                action.name()
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        (key_map, errors)
+    }
+}
+
+/// reads a key name like q, N, Enter, PageDown or F5
+/// letters are case sensitive, named keys are not
+pub fn parse_key(key: &str) -> Result<KeyCode, String> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+    let lower = key.to_lowercase();
+    let code = match lower.as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "space" => KeyCode::Char(' '),
+        _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+            _ => return Err(format!("Unknown key: {}", key)),
+        },
+    };
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(entries: &[(&str, &[&str])]) -> KeyBindings {
+        let mut bindings = KeyBindings::default();
+        for (name, keys) in entries {
+            bindings.0.insert(
+                name.to_string(),
+                keys.iter().map(|k| k.to_string()).collect(),
+            );
+        }
+        bindings
+    }
+
+    #[test]
+    fn parse_keys() {
+        assert_eq!(parse_key("q"), Ok(KeyCode::Char('q')));
+        assert_eq!(parse_key("N"), Ok(KeyCode::Char('N')));
+        assert_eq!(parse_key("$"), Ok(KeyCode::Char('$')));
+        assert_eq!(parse_key("PageDown"), Ok(KeyCode::PageDown));
+        assert_eq!(parse_key("pgup"), Ok(KeyCode::PageUp));
+        assert_eq!(parse_key("ESC"), Ok(KeyCode::Esc));
+        assert_eq!(parse_key("space"), Ok(KeyCode::Char(' ')));
+        assert_eq!(parse_key("F5"), Ok(KeyCode::F(5)));
+        assert!(parse_key("F13").is_err());
+        assert!(parse_key("ctrl").is_err());
+    }
+
+    #[test]
+    fn default_keys_have_no_conflicts() {
+        let (key_map, errors) = KeyBindings::default().key_map();
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(key_map.get(&KeyCode::Char('q')), Some(&Action::Quit));
+        assert_eq!(key_map.get(&KeyCode::Char('j')), Some(&Action::Down));
+    }
+
+    #[test]
+    fn config_keys_win_over_defaults() {
+        // Down comes after Sort and NextMatch after Quit, but the config bindings still win
+        let (key_map, errors) = bindings(&[("sort", &["j"]), ("quit", &["n"])]).key_map();
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(key_map.get(&KeyCode::Char('j')), Some(&Action::Sort));
+        assert_eq!(key_map.get(&KeyCode::Char('n')), Some(&Action::Quit));
+        // the other default keys of those actions still work
+        assert_eq!(key_map.get(&KeyCode::Down), Some(&Action::Down));
+        assert_eq!(
+            key_map.get(&KeyCode::Char('N')),
+            Some(&Action::PreviousMatch)
+        );
+        // a config without the action at all works the same way
+        let mut missing = bindings(&[("sort", &["j"])]);
+        missing.0.remove("down");
+        let (key_map, _) = missing.key_map();
+        assert_eq!(key_map.get(&KeyCode::Char('j')), Some(&Action::Sort));
+    }
+
+    #[test]
+    fn config_keys_conflict_with_each_other() {
+        let (key_map, errors) = bindings(&[("sort", &["x"]), ("copy", &["x"])]).key_map();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("x is bound to both"));
+        assert!(key_map.contains_key(&KeyCode::Char('x')));
+    }
+
+    #[test]
+    fn unknown_names_are_reported() {
+        let (_, errors) = bindings(&[("jump", &["x"]), ("sort", &["ctrl"])]).key_map();
+        assert!(errors
+            .iter()
+            .any(|e| e == "Unknown key binding action: jump"));
+        assert!(errors.iter().any(|e| e == "Unknown key: ctrl"));
+    }
+}
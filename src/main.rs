@@ -1,18 +1,45 @@
 use std::io;
 
 pub mod app;
+pub mod budget;
+pub mod cli;
 pub mod collection;
+pub mod commander;
+pub mod completion;
 pub mod config;
 pub mod database;
+pub mod diff;
+pub mod editor;
+pub mod filter;
+pub mod keybindings;
+pub mod missing;
+pub mod odds;
+pub mod report;
+pub mod search;
 pub mod startup;
+pub mod stats;
+pub mod substitute;
+pub mod trade;
 pub mod tui;
+pub mod value;
+pub mod wildcard;
 
 use app::App;
+use cli::run_cli;
 use ratatui_explorer::{FileExplorer, Theme};
 use tui::core::{init, restore};
 
 #[tokio::main]
 async fn main() -> Result<(), io::Error> {
+    // any arguments run a single command instead of the TUI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(e) = run_cli(&args) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     let mut terminal = init()?;
     let theme = Theme::default().add_default_title();
     let mut collection_explorer = FileExplorer::with_theme(theme.clone())?;
@@ -0,0 +1,292 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collection::{CollectionCard, DeckSection},
+    database::scryfall::{get_min_price, make_safe_name, PriceType, ScryfallCard},
+    wildcard::WildcardCost,
+};
+
+/// order of the cards on the Missing tab, also used for the file export and clipboard
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum MissingSort {
+    #[default]
+    Deck,
+    Name,
+    Quantity,
+    UnitPrice,
+    TotalPrice,
+    NotFound,
+    Type,
+    Cmc,
+    Rarity,
+    EdhrecRank,
+}
+
+impl MissingSort {
+    /// cycles to the next sort, skipping the ones that need the card database if it isn't loaded
+    pub fn next(self, has_database: bool) -> Self {
+        let next = match self {
+            MissingSort::Deck => MissingSort::Name,
+            MissingSort::Name => MissingSort::Quantity,
+            MissingSort::Quantity => MissingSort::UnitPrice,
+            MissingSort::UnitPrice => MissingSort::TotalPrice,
+            MissingSort::TotalPrice => MissingSort::NotFound,
+            MissingSort::NotFound => MissingSort::Type,
+            MissingSort::Type => MissingSort::Cmc,
+            MissingSort::Cmc => MissingSort::Rarity,
+            MissingSort::Rarity => MissingSort::EdhrecRank,
+            MissingSort::EdhrecRank => MissingSort::Deck,
+        };
+        if !has_database && next.needs_database() {
+            MissingSort::Deck
+        } else {
+            next
+        }
+    }
+
+    pub fn needs_database(&self) -> bool {
+        !matches!(
+            self,
+            MissingSort::Deck | MissingSort::Name | MissingSort::Quantity
+        )
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MissingSort::Deck => "Deck order",
+            MissingSort::Name => "Name",
+            MissingSort::Quantity => "Quantity missing",
+            MissingSort::UnitPrice => "Unit price",
+            MissingSort::TotalPrice => "Total price",
+            MissingSort::NotFound => "Not found first",
+            MissingSort::Type => "Card type",
+            MissingSort::Cmc => "Mana value",
+            MissingSort::Rarity => "Wildcard rarity",
+            MissingSort::EdhrecRank => "EDHREC rank",
+        }
+    }
+}
+
+/// the new position of every missing card for the selected sort
+/// deck order is the order the cards appear in the decklist
+/// prices are sorted most expensive first, everything else ties on name
+pub fn missing_order(
+    cards: &[CollectionCard],
+    decklist: &[CollectionCard],
+    sort: MissingSort,
+    database: &HashMap<String, ScryfallCard>,
+    currency: &PriceType,
+) -> Vec<usize> {
+    let matches: Vec<Option<&ScryfallCard>> = cards
+        .iter()
+        .map(|c| database.get(&make_safe_name(&c.name, true)))
+        .collect();
+    let unit_price = |i: usize| match matches[i] {
+        Some(m) => get_min_price(std::slice::from_ref(m), currency.clone()),
+        None => 0.0,
+    };
+    let deck_position = |i: usize| {
+        decklist
+            .iter()
+            .position(|d| d.name == cards[i].name)
+            .unwrap_or(usize::MAX)
+    };
+    let mut order: Vec<usize> = (0..cards.len()).collect();
+    order.sort_by(|a, b| {
+        let ordering = match sort {
+            MissingSort::Deck => deck_position(*a).cmp(&deck_position(*b)),
+            MissingSort::Name => Ordering::Equal,
+            MissingSort::Quantity => cards[*b].quantity.cmp(&cards[*a].quantity),
+            MissingSort::UnitPrice => unit_price(*b).total_cmp(&unit_price(*a)),
+            MissingSort::TotalPrice => (unit_price(*b) * cards[*b].quantity as f64)
+                .total_cmp(&(unit_price(*a) * cards[*a].quantity as f64)),
+            MissingSort::NotFound => matches[*b].is_none().cmp(&matches[*a].is_none()),
+            MissingSort::Type => type_rank(matches[*a]).cmp(&type_rank(matches[*b])),
+            MissingSort::Cmc => cmc(matches[*a]).total_cmp(&cmc(matches[*b])),
+            MissingSort::Rarity => {
+                WildcardCost::of(matches[*a]).cmp(&WildcardCost::of(matches[*b]))
+            }
+            MissingSort::EdhrecRank => edhrec_rank(matches[*a]).cmp(&edhrec_rank(matches[*b])),
+        };
+        ordering.then_with(|| cards[*a].name.cmp(&cards[*b].name))
+    });
+    order
+}
+
+/// rearranges a list to match an order from missing_order()
+/// lists that don't line up with the order are left alone
+pub fn apply_order<T: Clone>(items: &mut Vec<T>, order: &[usize]) {
+    if items.len() != order.len() {
+        return;
+    }
+    *items = order.iter().map(|i| items[*i].clone()).collect();
+}
+
+/// groups cards by their main type the way most deck builders do
+pub fn type_rank(card: Option<&ScryfallCard>) -> usize {
+    const TYPES: [&str; 8] = [
+        "Creature",
+        "Planeswalker",
+        "Battle",
+        "Instant",
+        "Sorcery",
+        "Artifact",
+        "Enchantment",
+        "Land",
+    ];
+    let type_line = match card.and_then(|c| c.type_line.as_ref()) {
+        Some(t) => t,
+        None => return TYPES.len() + 1,
+    };
+    // only look at the front face of double faced cards
+    let front = type_line.split("//").next().unwrap_or_default();
+    TYPES
+        .iter()
+        .position(|t| front.contains(t))
+        .unwrap_or(TYPES.len())
+}
+
+/// cards not in the database go last
+fn cmc(card: Option<&ScryfallCard>) -> f64 {
+    card.and_then(|c| c.cmc).unwrap_or(f64::MAX)
+}
+
+/// most played cards first, cards without a rank last
+fn edhrec_rank(card: Option<&ScryfallCard>) -> i64 {
+    card.map(|c| c.edhrec_rank)
+        .filter(|r| *r > 0)
+        .unwrap_or(i64::MAX)
+}
+
+/// file formats the missing list can be exported and copied in
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum MissingFormat {
+    #[default]
+    Text,
+    TcgPlayer,
+    Cardmarket,
+    CardKingdom,
+    Dek,
+}
+
+impl MissingFormat {
+    pub fn next(self) -> Self {
+        match self {
+            MissingFormat::Text => MissingFormat::TcgPlayer,
+            MissingFormat::TcgPlayer => MissingFormat::Cardmarket,
+            MissingFormat::Cardmarket => MissingFormat::CardKingdom,
+            MissingFormat::CardKingdom => MissingFormat::Dek,
+            MissingFormat::Dek => MissingFormat::Text,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MissingFormat::Text => "Text",
+            MissingFormat::TcgPlayer => "TCGplayer Mass Entry",
+            MissingFormat::Cardmarket => "Cardmarket wants",
+            MissingFormat::CardKingdom => "Card Kingdom",
+            MissingFormat::Dek => "MTGO .dek",
+        }
+    }
+
+    /// name of the exported file for a decklist file name
+    pub fn file_name(&self, decklist_name: &str) -> String {
+        let stem = decklist_name
+            .rsplit_once('.')
+            .map(|(stem, _)| stem)
+            .unwrap_or(decklist_name);
+        match self {
+            MissingFormat::Text => format!("missing_{}", decklist_name),
+            MissingFormat::TcgPlayer => format!("missing_{}_tcgplayer.txt", stem),
+            MissingFormat::Cardmarket => format!("missing_{}_cardmarket.txt", stem),
+            MissingFormat::CardKingdom => format!("missing_{}_cardkingdom.txt", stem),
+            MissingFormat::Dek => format!("missing_{}.dek", stem),
+        }
+    }
+}
+
+/// the missing cards in one of the export formats
+/// with set_codes the printing from the card database is added, cards that aren't in the
+/// database are written without one
+pub fn missing_list_string(
+    cards: &[CollectionCard],
+    database: &HashMap<String, ScryfallCard>,
+    format: MissingFormat,
+    set_codes: bool,
+) -> String {
+    let printing = |card: &CollectionCard| {
+        if set_codes {
+            database.get(&make_safe_name(&card.name, true))
+        } else {
+            None
+        }
+    };
+    let mut list = String::new();
+    if format == MissingFormat::Dek {
+        list += "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n";
+        list += "<Deck xmlns:xsd=\"http://www.w3.org/2001/XMLSchema\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\n";
+        list += "  <NetDeckID>0</NetDeckID>\n";
+        list += "  <PreconstructedDeckID>0</PreconstructedDeckID>\n";
+    }
+    for card in cards {
+        let printing = printing(card);
+        let line = match format {
+            MissingFormat::Text => match printing {
+                Some(p) => format!(
+                    "{} {} ({}) {}",
+                    card.quantity,
+                    card.name,
+                    p.set.to_uppercase(),
+                    p.collector_number
+                ),
+                None => card.to_string(),
+            },
+            MissingFormat::TcgPlayer => match printing {
+                Some(p) => format!("{} {} [{}]", card.quantity, card.name, p.set.to_uppercase()),
+                None => card.to_string(),
+            },
+            MissingFormat::Cardmarket => match printing {
+                Some(p) => format!("{}x {} ({})", card.quantity, card.name, p.set_name),
+                None => format!("{}x {}", card.quantity, card.name),
+            },
+            // Card Kingdom looks up double faced cards by their front face
+            MissingFormat::CardKingdom => {
+                let name = card.name.split(" // ").next().unwrap_or_default();
+                match printing {
+                    Some(p) => format!("{} {} [{}]", card.quantity, name, p.set_name),
+                    None => format!("{} {}", card.quantity, name),
+                }
+            }
+            MissingFormat::Dek => {
+                let cat_id = match printing {
+                    Some(p) if p.mtgo_id > 0 => format!("CatID=\"{}\" ", p.mtgo_id),
+                    _ => String::new(),
+                };
+                format!(
+                    "  <Cards {}Quantity=\"{}\" Sideboard=\"{}\" Name=\"{}\" Annotation=\"0\" />",
+                    cat_id,
+                    card.quantity,
+                    card.section == DeckSection::Sideboard,
+                    xml_escape(&card.name)
+                )
+            }
+        };
+        list += &line;
+        list.push('\n');
+    }
+    if format == MissingFormat::Dek {
+        list += "</Deck>\n";
+    }
+    list
+}
+
+/// escapes the characters that can't go in an XML attribute
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,194 @@
+use crate::collection::{CollectionCard, DeckSection};
+
+/// cards in an opening hand
+pub const HAND_SIZE: u64 = 7;
+
+/// number of cards seen by the given turn, including the opening hand
+/// the player on the play skips their first draw
+pub fn cards_seen(turn: u64, on_play: bool) -> u64 {
+    if on_play {
+        HAND_SIZE + turn.saturating_sub(1)
+    } else {
+        HAND_SIZE + turn
+    }
+}
+
+/// number of ways to choose k items from n
+fn choose(n: u64, k: u64) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// hypergeometric odds of drawing at least `at_least` of the `successes` cards when drawing
+/// `draws` cards from a deck of `population` cards
+pub fn prob_at_least(population: u64, successes: u64, draws: u64, at_least: u64) -> f64 {
+    let draws = draws.min(population);
+    let successes = successes.min(population);
+    if at_least == 0 {
+        return 1.0;
+    }
+    let total = choose(population, draws);
+    if total == 0.0 {
+        return 0.0;
+    }
+    let below: f64 = (0..at_least.min(draws + 1))
+        .map(|i| choose(successes, i) * choose(population - successes, draws.saturating_sub(i)))
+        .sum::<f64>()
+        / total;
+    (1.0 - below).clamp(0.0, 1.0)
+}
+
+/// odds of at least `at_least` of `successes` cards by the given turn
+pub fn prob_by_turn(
+    population: u64,
+    successes: u64,
+    at_least: u64,
+    turn: u64,
+    on_play: bool,
+) -> f64 {
+    prob_at_least(population, successes, cards_seen(turn, on_play), at_least)
+}
+
+/// estimates the odds of prob_at_least by drawing `trials` random hands, seeded like sample_hands
+pub fn sampled_prob_at_least(
+    population: u64,
+    successes: u64,
+    draws: u64,
+    at_least: u64,
+    trials: u64,
+    seed: u64,
+) -> f64 {
+    if trials == 0 || population == 0 {
+        return 0.0;
+    }
+    let draws = draws.min(population) as usize;
+    let successes = successes.min(population);
+    // the cards that count are the first `successes` in the library
+    let mut library: Vec<u64> = (0..population).collect();
+    let mut rng = SeededRng::new(seed);
+    let mut hits = 0;
+    for _ in 0..trials {
+        for i in 0..draws {
+            let j = i + rng.below((library.len() - i) as u64) as usize;
+            library.swap(i, j);
+        }
+        let found = library[..draws].iter().filter(|c| **c < successes).count() as u64;
+        if found >= at_least {
+            hits += 1;
+        }
+    }
+    hits as f64 / trials as f64
+}
+
+/// small seeded random number generator (splitmix64) so sample hands can be repeated
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        SeededRng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// random number from 0 up to (not including) max
+    pub fn below(&mut self, max: u64) -> u64 {
+        if max == 0 {
+            return 0;
+        }
+        // reject the top of the range so every number is equally likely
+        let zone = u64::MAX - u64::MAX % max;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % max;
+            }
+        }
+    }
+}
+
+/// draws random opening hands from the main deck, the same seed always gives the same hands
+pub fn sample_hands(
+    decklist: &[CollectionCard],
+    hand_size: u64,
+    count: usize,
+    seed: u64,
+) -> Vec<Vec<String>> {
+    let mut library: Vec<&str> = Vec::new();
+    for card in decklist.iter().filter(|c| c.section == DeckSection::Main) {
+        for _ in 0..card.quantity {
+            library.push(&card.name);
+        }
+    }
+    let mut rng = SeededRng::new(seed);
+    let hand_size = (hand_size as usize).min(library.len());
+    let mut hands = Vec::new();
+    for _ in 0..count {
+        // partial Fisher-Yates shuffle, only the cards in the hand need to be picked
+        for i in 0..hand_size {
+            let j = i + rng.below((library.len() - i) as u64) as usize;
+            library.swap(i, j);
+        }
+        let mut hand: Vec<String> = library[..hand_size].iter().map(|s| s.to_string()).collect();
+        hand.sort();
+        hands.push(hand);
+    }
+    hands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deck() -> Vec<CollectionCard> {
+        [
+            ("Island", 20),
+            ("Counterspell", 4),
+            ("Brainstorm", 4),
+            ("Ponder", 32),
+        ]
+        .into_iter()
+        .map(|(name, quantity)| CollectionCard {
+            name: name.to_string(),
+            quantity,
+            ..Default::default()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn same_seed_same_hands() {
+        let decklist = deck();
+        let first = sample_hands(&decklist, HAND_SIZE, 5, 42);
+        assert_eq!(first, sample_hands(&decklist, HAND_SIZE, 5, 42));
+        assert_ne!(first, sample_hands(&decklist, HAND_SIZE, 5, 43));
+        assert!(first.iter().all(|h| h.len() == HAND_SIZE as usize));
+    }
+
+    #[test]
+    fn hypergeometric_odds() {
+        // 4 copies in 60 cards, at least 1 in a 7 card opening hand
+        assert!((prob_at_least(60, 4, 7, 1) - 0.3995).abs() < 0.0001);
+        assert!((prob_at_least(60, 4, 7, 2) - 0.0632).abs() < 0.0001);
+        assert_eq!(prob_at_least(60, 4, 7, 0), 1.0);
+        assert_eq!(prob_at_least(60, 4, 7, 5), 0.0);
+        assert_eq!(prob_at_least(60, 60, 7, 7), 1.0);
+    }
+
+    #[test]
+    fn sampled_odds_close_to_exact() {
+        let sampled = sampled_prob_at_least(60, 4, 7, 1, 20_000, 7);
+        assert!((sampled - prob_at_least(60, 4, 7, 1)).abs() < 0.02);
+        assert_eq!(sampled, sampled_prob_at_least(60, 4, 7, 1, 20_000, 7));
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collection::{CollectionCard, DeckSection, FormatLegal},
+    database::scryfall::{
+        format_label, get_min_price, match_card, PriceType, ScryfallCard, ScryfallPurchase,
+        FORMAT_NAMES,
+    },
+};
+
+/// file formats the shopping report can be saved in
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// name of the report file for a decklist file name
+    pub fn file_name(&self, decklist_name: &str) -> String {
+        let stem = decklist_name
+            .rsplit_once('.')
+            .map(|(stem, _)| stem)
+            .unwrap_or(decklist_name);
+        match self {
+            ReportFormat::Markdown => format!("shopping_{}.md", stem),
+            ReportFormat::Html => format!("shopping_{}.html", stem),
+        }
+    }
+}
+
+/// one missing card with its price and store links, None if it isn't in the database
+struct ReportCard<'a> {
+    card: &'a CollectionCard,
+    unit_price: Option<f64>,
+    links: Vec<(&'static str, String)>,
+}
+
+/// a shopping list for the missing cards of a deck, with prices, store links, cards that
+/// weren't found in the database, the grand total and the deck's legality
+pub fn shopping_report(
+    deck_name: &str,
+    cards: &[CollectionCard],
+    database: &HashMap<String, ScryfallCard>,
+    currency: &PriceType,
+    legality: Option<&FormatLegal>,
+    format: ReportFormat,
+) -> String {
+    let report_cards: Vec<ReportCard> = cards
+        .iter()
+        .map(|card| match match_card(&card.name, database) {
+            Some(m) => ReportCard {
+                card,
+                unit_price: Some(get_min_price(std::slice::from_ref(&m), currency.clone())),
+                links: store_links(&m.purchase_uris),
+            },
+            None => ReportCard {
+                card,
+                unit_price: None,
+                links: Vec::new(),
+            },
+        })
+        .collect();
+    let total: f64 = report_cards
+        .iter()
+        .map(|c| c.unit_price.unwrap_or(0.0) * c.card.quantity as f64)
+        .sum();
+    let (legal, not_legal) = match legality {
+        Some(l) => {
+            let (legal, not_legal): (Vec<&str>, Vec<&str>) = FORMAT_NAMES
+                .into_iter()
+                .partition(|f| l.get(f) == Some(true));
+            (
+                legal.into_iter().map(format_label).collect::<Vec<&str>>(),
+                not_legal
+                    .into_iter()
+                    .map(format_label)
+                    .collect::<Vec<&str>>(),
+            )
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+    let symbol = currency.symbol();
+    let price = |p: f64| format!("{}{:.2}", symbol, p);
+    let date = Local::now().format("%Y-%m-%d");
+    let mut report = String::new();
+    match format {
+        ReportFormat::Markdown => {
+            report += &format!("# Shopping list for {}\n\n", deck_name);
+            report += &format!("Prices from Scryfall, {}.\n\n", date);
+            report += "| Qty | Card | Unit | Total | Buy |\n";
+            report += "| --: | --- | --: | --: | --- |\n";
+            for c in report_cards.iter() {
+                let (unit, line_total) = match c.unit_price {
+                    Some(p) => (price(p), price(p * c.card.quantity as f64)),
+                    None => ("-".to_string(), "-".to_string()),
+                };
+                let links: Vec<String> = c
+                    .links
+                    .iter()
+                    .map(|(store, uri)| format!("[{}]({})", store, uri))
+                    .collect();
+                report += &format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    c.card.quantity,
+                    card_name(c.card).replace('|', "\\|"),
+                    unit,
+                    line_total,
+                    links.join(" ")
+                );
+            }
+            report += &format!("\n**Total: {}**\n", price(total));
+            let not_found: Vec<&ReportCard> = report_cards
+                .iter()
+                .filter(|c| c.unit_price.is_none())
+                .collect();
+            if !not_found.is_empty() {
+                report += "\n## Not in the database\n\n";
+                report +=
+                    "These cards aren't priced or counted in the total, check the spelling.\n\n";
+                for c in not_found {
+                    report += &format!("- {}\n", c.card);
+                }
+            }
+            report += "\n## Legality\n\n";
+            match legality {
+                Some(l) => {
+                    report += &format!("- **Legal:** {}\n", join_or_none(&legal));
+                    report += &format!("- **Not legal:** {}\n", join_or_none(&not_legal));
+                    if !l.not_in_game.is_empty() {
+                        report += &format!(
+                            "- **Not in the collection's game:** {}\n",
+                            l.not_in_game.join(", ")
+                        );
+                    }
+                }
+                None => report += "Legality wasn't checked, it needs the card database.\n",
+            }
+        }
+        ReportFormat::Html => {
+            let title = format!("Shopping list for {}", html_escape(deck_name));
+            report += "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n";
+            report += &format!("<title>{}</title>\n", title);
+            report += "<style>\n";
+            report += "body { font-family: sans-serif; margin: 2em; }\n";
+            report += "table { border-collapse: collapse; }\n";
+            report += "th, td { border: 1px solid #999; padding: 0.3em 0.6em; }\n";
+            report += "td.num { text-align: right; }\n";
+            report += ".warning { color: #b00; }\n";
+            report += "</style>\n</head>\n<body>\n";
+            report += &format!("<h1>{}</h1>\n", title);
+            report += &format!("<p>Prices from Scryfall, {}.</p>\n", date);
+            report += "<table>\n<tr><th>Qty</th><th>Card</th><th>Unit</th><th>Total</th><th>Buy</th></tr>\n";
+            for c in report_cards.iter() {
+                let (unit, line_total) = match c.unit_price {
+                    Some(p) => (price(p), price(p * c.card.quantity as f64)),
+                    None => (
+                        "-".to_string(),
+                        "<span class=\"warning\">not in database</span>".to_string(),
+                    ),
+                };
+                let links: Vec<String> = c
+                    .links
+                    .iter()
+                    .map(|(store, uri)| format!("<a href=\"{}\">{}</a>", html_escape(uri), store))
+                    .collect();
+                report += &format!(
+                    "<tr><td class=\"num\">{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td></tr>\n",
+                    c.card.quantity,
+                    html_escape(&card_name(c.card)),
+                    unit,
+                    line_total,
+                    links.join(" ")
+                );
+            }
+            report += "</table>\n";
+            report += &format!("<p><strong>Total: {}</strong></p>\n", price(total));
+            let not_found: Vec<&ReportCard> = report_cards
+                .iter()
+                .filter(|c| c.unit_price.is_none())
+                .collect();
+            if !not_found.is_empty() {
+                report += "<h2>Not in the database</h2>\n";
+                report += "<p class=\"warning\">These cards aren't priced or counted in the total, check the spelling.</p>\n<ul>\n";
+                for c in not_found {
+                    report += &format!("<li>{}</li>\n", html_escape(&c.card.to_string()));
+                }
+                report += "</ul>\n";
+            }
+            report += "<h2>Legality</h2>\n";
+            match legality {
+                Some(l) => {
+                    report += &format!("<p><strong>Legal:</strong> {}</p>\n", join_or_none(&legal));
+                    report += &format!(
+                        "<p><strong>Not legal:</strong> {}</p>\n",
+                        join_or_none(&not_legal)
+                    );
+                    if !l.not_in_game.is_empty() {
+                        let names: Vec<String> =
+                            l.not_in_game.iter().map(|n| html_escape(n)).collect();
+                        report += &format!(
+                            "<p><strong>Not in the collection's game:</strong> {}</p>\n",
+                            names.join(", ")
+                        );
+                    }
+                }
+                None => report += "<p>Legality wasn't checked, it needs the card database.</p>\n",
+            }
+            report += "</body>\n</html>\n";
+        }
+    }
+    report
+}
+
+/// the stores Scryfall has links for
+fn store_links(purchase: &ScryfallPurchase) -> Vec<(&'static str, String)> {
+    [
+        ("TCGplayer", &purchase.tcgplayer),
+        ("Cardmarket", &purchase.cardmarket),
+        ("Cardhoarder", &purchase.cardhoarder),
+    ]
+    .into_iter()
+    .filter(|(_, uri)| !uri.is_empty())
+    .map(|(store, uri)| (store, uri.clone()))
+    .collect()
+}
+
+/// sideboard cards are marked so they aren't mistaken for duplicates
+fn card_name(card: &CollectionCard) -> String {
+    match card.section {
+        DeckSection::Sideboard => format!("{} (sideboard)", card.name),
+        _ => card.name.clone(),
+    }
+}
+
+fn join_or_none(formats: &[&str]) -> String {
+    if formats.is_empty() {
+        "none".to_string()
+    } else {
+        formats.join(", ")
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
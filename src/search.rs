@@ -0,0 +1,482 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use diacritics::remove_diacritics;
+
+use crate::{
+    collection::CollectionCard,
+    database::{
+        scryfall::{make_safe_name, Legality, MtGColors, PriceType, ScryfallCard},
+        sets::ScryfallSet,
+    },
+};
+
+/// names of every card printed in each set the query asks about, keyed by set code
+/// the card database only keeps one printing of each card, so s: needs the set index
+pub type SetNames = HashMap<String, HashSet<String>>;
+
+/// the search keywords that are supported, shown when a query can't be parsed
+pub const SEARCH_HELP: &str = "t:type o:text c:colors id:identity cmc>=N f:format r:rarity s:set usd<N eur<N tix<N is:owned, prefix - to negate";
+
+/// how a search value is compared to the card
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessEq,
+    Equal,
+    NotEqual,
+    GreaterEq,
+    Greater,
+    Default, // ":" - what this means depends on the keyword
+}
+
+impl Comparison {
+    /// splits an operator off the front of the text, e.g. "<=3" -> (LessEq, "3")
+    pub fn split(text: &str) -> Option<(Comparison, &str)> {
+        let operators = [
+            ("<=", Comparison::LessEq),
+            (">=", Comparison::GreaterEq),
+            ("!=", Comparison::NotEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            ("=", Comparison::Equal),
+            (":", Comparison::Default),
+        ];
+        operators
+            .into_iter()
+            .find_map(|(op, comparison)| text.strip_prefix(op).map(|v| (comparison, v)))
+    }
+
+    pub fn compare<T: PartialOrd>(&self, left: T, right: T) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessEq => left <= right,
+            Comparison::Equal | Comparison::Default => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::GreaterEq => left >= right,
+            Comparison::Greater => left > right,
+        }
+    }
+}
+
+/// a single condition in a search query
+#[derive(Debug, Clone)]
+pub enum SearchTerm {
+    Name(String),
+    Type(String),
+    Oracle(String),
+    Colors(Comparison, u8),
+    Identity(Comparison, u8),
+    Cmc(Comparison, f64),
+    Format(String),
+    Rarity(Comparison, u8),
+    Set(String),
+    Price(PriceType, Comparison, f64),
+    Owned,
+}
+
+/// a parsed search query, every term has to match (terms can be negated)
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    pub terms: Vec<(bool, SearchTerm)>, // (negated, term)
+}
+
+impl FromStr for SearchQuery {
+    type Err = String;
+
+    /// parses a subset of Scryfall's search syntax
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut terms = Vec::new();
+        for word in split_words(s) {
+            let (negated, word) = match word.strip_prefix('-') {
+                Some(w) if !w.is_empty() => (true, w.to_string()),
+                _ => (false, word),
+            };
+            terms.push((negated, parse_term(&word)?));
+        }
+        Ok(SearchQuery { terms })
+    }
+}
+
+impl SearchQuery {
+    /// checks a card against every term in the query
+    /// owned is the number of copies in the collection
+    pub fn matches(&self, card: &ScryfallCard, owned: u64, set_names: &SetNames) -> bool {
+        self.terms
+            .iter()
+            .all(|(negated, term)| term_matches(term, card, owned, set_names) != *negated)
+    }
+
+    /// collects the card names in every set used by an s: term
+    pub fn set_names(&self, sets: &HashMap<String, ScryfallSet>) -> SetNames {
+        let mut set_names = SetNames::new();
+        for (_, term) in self.terms.iter() {
+            if let SearchTerm::Set(code) = term {
+                if let Some(set) = sets.get(code) {
+                    set_names.insert(
+                        code.clone(),
+                        set.printings.iter().map(|p| p.name.clone()).collect(),
+                    );
+                }
+            }
+        }
+        set_names
+    }
+}
+
+/// a card that matched a search
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    pub name: String,
+    pub mana_cost: String,
+    pub type_line: String,
+    pub set: String,
+    pub rarity: String,
+    pub price: Option<f64>,
+    pub owned: u64,
+}
+
+/// runs a search query over the whole card database
+/// collection is used for is:owned and the owned quantity of each result
+pub async fn search_database(
+    query: &SearchQuery,
+    database: &HashMap<String, ScryfallCard>,
+    sets: &HashMap<String, ScryfallSet>,
+    collection: &[CollectionCard],
+    currency: PriceType,
+) -> Vec<SearchResult> {
+    let set_names = query.set_names(sets);
+    let mut owned_map: HashMap<String, u64> = HashMap::new();
+    for card in collection {
+        *owned_map
+            .entry(make_safe_name(&card.name, true))
+            .or_insert(0) += card.quantity;
+    }
+    let mut results = Vec::new();
+    for (safe_name, card) in database.iter() {
+        let owned = *owned_map.get(safe_name).unwrap_or(&0);
+        if query.matches(card, owned, &set_names) {
+            results.push(SearchResult {
+                name: card.name.clone(),
+                mana_cost: card.mana_cost.clone(),
+                type_line: card.type_line.clone().unwrap_or_default(),
+                set: card.set.to_uppercase(),
+                rarity: card.rarity.to_string(),
+                price: card.prices.get(&currency),
+                owned,
+            });
+        }
+    }
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    results
+}
+
+/// splits a query on whitespace, keeping "quoted text" together
+fn split_words(query: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn parse_term(word: &str) -> Result<SearchTerm, String> {
+    // find the first operator, anything before it is the keyword
+    let op_start = match word.find([':', '=', '<', '>', '!']) {
+        Some(i) => i,
+        None => return Ok(SearchTerm::Name(normalize(word))),
+    };
+    let keyword = word[..op_start].to_lowercase();
+    let (comparison, value) = match Comparison::split(&word[op_start..]) {
+        Some(split) => split,
+        None => return Err(format!("Unknown operator in {}", word)),
+    };
+    if value.is_empty() {
+        return Err(format!("Missing value in {}", word));
+    }
+    let value_lower = value.to_lowercase();
+    match keyword.as_str() {
+        "t" | "type" => Ok(SearchTerm::Type(value_lower)),
+        "o" | "oracle" => Ok(SearchTerm::Oracle(value_lower)),
+        "c" | "color" => Ok(SearchTerm::Colors(comparison, parse_colors(&value_lower)?)),
+        "id" | "identity" => Ok(SearchTerm::Identity(
+            comparison,
+            parse_colors(&value_lower)?,
+        )),
+        "cmc" | "mv" => Ok(SearchTerm::Cmc(comparison, parse_number(value)?)),
+        "f" | "format" | "legal" => Ok(SearchTerm::Format(value_lower)),
+        "r" | "rarity" => Ok(SearchTerm::Rarity(comparison, parse_rarity(&value_lower)?)),
+        "s" | "set" | "e" | "edition" => Ok(SearchTerm::Set(value_lower)),
+        "usd" => Ok(SearchTerm::Price(
+            PriceType::USD,
+            comparison,
+            parse_number(value)?,
+        )),
+        "eur" => Ok(SearchTerm::Price(
+            PriceType::Euro,
+            comparison,
+            parse_number(value)?,
+        )),
+        "tix" => Ok(SearchTerm::Price(
+            PriceType::Tix,
+            comparison,
+            parse_number(value)?,
+        )),
+        "is" if value_lower == "owned" => Ok(SearchTerm::Owned),
+        "name" => Ok(SearchTerm::Name(normalize(value))),
+        _ => Err(format!("Unknown search keyword: {}", word)),
+    }
+}
+
+pub fn parse_number(value: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .map_err(|_| format!("{} is not a number", value))
+}
+
+/// colors are stored as a bitmask so they can be compared as sets
+fn color_bit(color: &MtGColors) -> u8 {
+    match color {
+        MtGColors::White => 1,
+        MtGColors::Blue => 2,
+        MtGColors::Black => 4,
+        MtGColors::Red => 8,
+        MtGColors::Green => 16,
+    }
+}
+
+fn color_mask(colors: &Option<Vec<MtGColors>>) -> u8 {
+    match colors {
+        Some(c) => c.iter().fold(0, |mask, color| mask | color_bit(color)),
+        None => 0,
+    }
+}
+
+/// colors of the card, or of all its faces for double faced cards that only have colors on each face
+fn card_color_mask(card: &ScryfallCard) -> u8 {
+    match card.colors {
+        Some(_) => color_mask(&card.colors),
+        None => card
+            .card_faces
+            .iter()
+            .fold(0, |mask, face| mask | color_mask(&face.colors)),
+    }
+}
+
+/// accepts color letters (wubrg), full color names, c/colorless and m/multicolor
+/// multicolor is stored as u8::MAX since it can't be a real mask
+fn parse_colors(value: &str) -> Result<u8, String> {
+    match value {
+        "c" | "colorless" => return Ok(0),
+        "m" | "multicolor" => return Ok(u8::MAX),
+        "white" => return Ok(1),
+        "blue" => return Ok(2),
+        "black" => return Ok(4),
+        "red" => return Ok(8),
+        "green" => return Ok(16),
+        _ => {}
+    }
+    let mut mask = 0;
+    for c in value.chars() {
+        mask |= match c {
+            'w' => 1,
+            'u' => 2,
+            'b' => 4,
+            'r' => 8,
+            'g' => 16,
+            _ => return Err(format!("Unknown color: {}", value)),
+        };
+    }
+    Ok(mask)
+}
+
+fn parse_rarity(value: &str) -> Result<u8, String> {
+    match value {
+        "c" | "common" => Ok(0),
+        "u" | "uncommon" => Ok(1),
+        "r" | "rare" => Ok(2),
+        "m" | "mythic" => Ok(3),
+        "s" | "special" => Ok(4),
+        "b" | "bonus" => Ok(5),
+        _ => Err(format!("Unknown rarity: {}", value)),
+    }
+}
+
+/// compares two color masks as sets
+/// is_identity changes what ":" means - colors use "includes", identity uses "fits within"
+fn compare_colors(card: u8, search: u8, comparison: Comparison, is_identity: bool) -> bool {
+    if search == u8::MAX {
+        return card.count_ones() > 1;
+    }
+    let subset = card & search == card;
+    let superset = card & search == search;
+    match comparison {
+        Comparison::Default if is_identity => subset,
+        Comparison::Default => superset,
+        Comparison::Equal => card == search,
+        Comparison::NotEqual => card != search,
+        Comparison::LessEq => subset,
+        Comparison::Less => subset && card != search,
+        Comparison::GreaterEq => superset,
+        Comparison::Greater => superset && card != search,
+    }
+}
+
+fn term_matches(term: &SearchTerm, card: &ScryfallCard, owned: u64, set_names: &SetNames) -> bool {
+    match term {
+        SearchTerm::Name(name) => normalize(&card.name).contains(name.as_str()),
+        SearchTerm::Type(type_str) => card
+            .type_line
+            .as_ref()
+            .is_some_and(|t| t.to_lowercase().contains(type_str.as_str())),
+        SearchTerm::Oracle(text) => {
+            // Scryfall uses ~ for the card's own name
+            let oracle = card.face_oracle_text().to_lowercase();
+            oracle.contains(text.as_str())
+                || oracle
+                    .replace(&card.name.to_lowercase(), "~")
+                    .contains(text.as_str())
+        }
+        SearchTerm::Colors(comparison, mask) => {
+            compare_colors(card_color_mask(card), *mask, *comparison, false)
+        }
+        SearchTerm::Identity(comparison, mask) => {
+            compare_colors(color_mask(&card.color_identity), *mask, *comparison, true)
+        }
+        SearchTerm::Cmc(comparison, cmc) => match card.cmc {
+            Some(card_cmc) => comparison.compare(card_cmc, *cmc),
+            None => false,
+        },
+        SearchTerm::Format(format) => matches!(
+            card.legalities.get(format),
+            Some(Legality::Legal | Legality::Restricted)
+        ),
+        SearchTerm::Rarity(comparison, rank) => comparison.compare(card.rarity.rank(), *rank),
+        SearchTerm::Set(set) => {
+            card.set == *set || set_names.get(set).is_some_and(|n| n.contains(&card.name))
+        }
+        SearchTerm::Price(currency, comparison, price) => match card.prices.get(currency) {
+            Some(card_price) => comparison.compare(card_price, *price),
+            None => false,
+        },
+        SearchTerm::Owned => owned > 0,
+    }
+}
+
+/// lowercase with diacritics removed, so "Lim-Dul" finds "Lim-Dûl"
+pub fn normalize(text: &str) -> String {
+    remove_diacritics(text).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counterspell() -> ScryfallCard {
+        let mut card =
+            ScryfallCard::test_card("Counterspell", "Instant", "{U}{U}", "Counter target spell.");
+        card.cmc = Some(2.0);
+        card.colors = Some(vec![MtGColors::Blue]);
+        card.color_identity = Some(vec![MtGColors::Blue]);
+        card.legalities.pauper = Legality::Legal;
+        card.prices.usd = Some("1.50".to_string());
+        card
+    }
+
+    fn matches(query: &str, card: &ScryfallCard) -> bool {
+        let query: SearchQuery = query.parse().unwrap();
+        query.matches(card, 0, &SetNames::new())
+    }
+
+    #[test]
+    fn quoted_words_and_negation() {
+        let query: SearchQuery = r#"-t:creature o:"counter target" bolt"#.parse().unwrap();
+        assert_eq!(query.terms.len(), 3);
+        assert!(matches!(&query.terms[0], (true, SearchTerm::Type(t)) if t == "creature"));
+        assert!(matches!(&query.terms[1], (false, SearchTerm::Oracle(o)) if o == "counter target"));
+        assert!(matches!(&query.terms[2], (false, SearchTerm::Name(n)) if n == "bolt"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!("cmc>=x".parse::<SearchQuery>().is_err());
+        assert!("c:purple".parse::<SearchQuery>().is_err());
+        assert!("r:epic".parse::<SearchQuery>().is_err());
+        assert!("t:".parse::<SearchQuery>().is_err());
+        assert!("foo:bar".parse::<SearchQuery>().is_err());
+        assert!("".parse::<SearchQuery>().unwrap().terms.is_empty());
+    }
+
+    #[test]
+    fn card_matches() {
+        let card = counterspell();
+        assert!(matches(
+            "counter t:instant c:u cmc<=2 f:pauper usd<2",
+            &card
+        ));
+        assert!(matches("id<=ub -c:m r:common", &card));
+        assert!(matches("o:\"counter target\" -o:draw", &card));
+        assert!(!matches("c:ub", &card));
+        assert!(!matches("f:modern", &card));
+        assert!(!matches("tix<1", &card));
+        assert!(!matches("is:owned", &card));
+        assert!("is:owned"
+            .parse::<SearchQuery>()
+            .unwrap()
+            .matches(&card, 4, &SetNames::new()));
+    }
+
+    #[test]
+    fn color_comparisons() {
+        let (white, blue) = (1, 2);
+        assert!(compare_colors(
+            white | blue,
+            white,
+            Comparison::Default,
+            false
+        ));
+        assert!(!compare_colors(
+            white | blue,
+            white,
+            Comparison::Default,
+            true
+        ));
+        assert!(compare_colors(
+            white,
+            white | blue,
+            Comparison::Default,
+            true
+        ));
+        assert!(compare_colors(
+            white | blue,
+            u8::MAX,
+            Comparison::Equal,
+            false
+        ));
+        assert!(compare_colors(0, 0, Comparison::Equal, false));
+        assert!(!compare_colors(white, white, Comparison::Greater, false));
+    }
+
+    #[test]
+    fn names_without_diacritics() {
+        assert_eq!(
+            normalize("Lim-Dûl the Necromancer"),
+            "lim-dul the necromancer"
+        );
+    }
+}
@@ -14,7 +14,11 @@ use ureq::Agent;
 
 use crate::{
     config::DecklistConfig,
-    database::scryfall::{read_decklist_database, read_scryfall_database, PriceType, ScryfallCard},
+    database::{
+        history::{date_from_filename, read_price_history, record_price_history, PriceHistory},
+        scryfall::{read_decklist_database, read_scryfall_database, PriceType, ScryfallCard},
+        sets::{read_sets_database, sets_file_name, ScryfallSet},
+    },
 };
 
 /*
@@ -115,6 +119,8 @@ pub struct DatabaseCheck {
     pub need_dl: bool,
     pub ready_load: bool,
     pub db_type: DatabaseType,
+    pub price_history: PriceHistory,
+    pub sets: HashMap<String, ScryfallSet>,
 }
 
 impl Default for DatabaseCheck {
@@ -128,6 +134,8 @@ impl Default for DatabaseCheck {
             need_dl: false,
             ready_load: false,
             db_type: DatabaseType::Scryfall,
+            price_history: PriceHistory::default(),
+            sets: HashMap::new(),
         }
     }
 }
@@ -199,11 +207,15 @@ pub async fn database_check(data_path: PathBuf, max_age: u64) -> DatabaseCheck {
         ready_load,
         filename,
         db_type,
+        price_history: PriceHistory::default(),
+        sets: HashMap::new(),
     }
 }
 
 /// attempts to load given database file
 /// updates status accordingly
+/// loading a new Scryfall file also records a snapshot of its prices in the price history kept
+/// next to the database
 pub async fn load_database_file(mut dc: DatabaseCheck, currency: PriceType) -> DatabaseCheck {
     let mut data_path = dc.database_path.clone();
     data_path.push(dc.filename.clone());
@@ -215,21 +227,40 @@ pub async fn load_database_file(mut dc: DatabaseCheck, currency: PriceType) -> D
                 dc.database_status = format!("Loaded cards from: {}", dc.filename);
                 dc.database_cards = cards;
                 dc.ready_load = false;
+                // older versions didn't save a set index, so it's fine if this one is missing
+                let sets_path = dc.database_path.join(sets_file_name(&dc.filename));
+                if let Ok(sets) = read_sets_database(&sets_path) {
+                    dc.sets = sets;
+                }
             }
             Err(e) => dc.database_status = e.to_string(),
         },
         DatabaseType::Scryfall => {
             match read_scryfall_database(&data_path, currency) {
-                Ok(cards) => {
+                Ok((cards, sets)) => {
                     dc.database_exists = true;
                     dc.database_status = format!("Loaded cards from: {}", dc.filename);
                     dc.database_cards = cards;
+                    dc.sets = sets;
                     dc.ready_load = false; // file loaded successfully, don't need to do again
+                    let date = date_from_filename(&dc.filename);
+                    match record_price_history(&dc.database_path, date, &dc.database_cards) {
+                        Ok(history) => dc.price_history = history,
+                        Err(e) => {
+                            dc.database_status += &format!("  Price history not updated: {}", e)
+                        }
+                    }
                 }
                 Err(e) => dc.database_status = e.to_string(),
             }
         }
     }
+    // Scryfall files already read the history above
+    if dc.db_type == DatabaseType::Decklist {
+        if let Ok(history) = read_price_history(&dc.database_path) {
+            dc.price_history = history;
+        }
+    }
     dc
 }
 
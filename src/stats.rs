@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use crate::{
+    collection::{CollectionCard, DeckSection},
+    database::scryfall::{match_card, ScryfallCard},
+};
+
+/// mana values on the curve, the last bucket is everything at or above it
+pub const CURVE_BUCKETS: [&str; 8] = ["0", "1", "2", "3", "4", "5", "6", "7+"];
+
+/// mana symbols counted as color pips, in WUBRG order plus colorless
+pub const PIP_SYMBOLS: [char; 6] = ['W', 'U', 'B', 'R', 'G', 'C'];
+
+/// statistics for the main deck of the loaded decklist, counting every copy of a card
+#[derive(Clone, Debug, Default)]
+pub struct DeckStats {
+    pub curve: [u64; 8], // nonland cards at each mana value, see CURVE_BUCKETS
+    pub pips: [u64; 6],  // see PIP_SYMBOLS
+    pub lands: u64,
+    pub creatures: u64,
+    pub spells: u64, // everything that isn't a land or a creature
+    pub total: u64,
+    pub average_cmc: f64, // lands are left out
+    pub not_found: u64,   // cards that weren't in the database and aren't counted above
+}
+
+impl DeckStats {
+    /// a rough land count for the deck's curve
+    /// 14 + 3 lands per point of average mana value in 60 cards, scaled to the deck size
+    pub fn suggested_lands(&self) -> u64 {
+        let per_sixty = 14.0 + 3.0 * self.average_cmc;
+        (per_sixty * self.total as f64 / 60.0).round() as u64
+    }
+}
+
+/// builds the deck statistics from the decklist and the card database
+pub async fn deck_stats(
+    decklist: &[CollectionCard],
+    database: &HashMap<String, ScryfallCard>,
+) -> DeckStats {
+    let mut stats = DeckStats::default();
+    let mut cmc_total = 0.0;
+    for card in decklist.iter().filter(|c| c.section == DeckSection::Main) {
+        stats.total += card.quantity;
+        let matched = match match_card(&card.name, database) {
+            Some(m) => m,
+            None => {
+                stats.not_found += card.quantity;
+                continue;
+            }
+        };
+        for (i, pip) in PIP_SYMBOLS.iter().enumerate() {
+            stats.pips[i] += count_pips(matched.face_mana_cost(), *pip) * card.quantity;
+        }
+        // only the front face counts for double faced cards
+        let type_line = matched.type_line.clone().unwrap_or_default();
+        let front = type_line.split("//").next().unwrap_or_default();
+        if front.contains("Land") {
+            stats.lands += card.quantity;
+            continue;
+        }
+        if front.contains("Creature") {
+            stats.creatures += card.quantity;
+        } else {
+            stats.spells += card.quantity;
+        }
+        let cmc = matched.cmc.unwrap_or(0.0);
+        cmc_total += cmc * card.quantity as f64;
+        let bucket = (cmc as usize).min(CURVE_BUCKETS.len() - 1);
+        stats.curve[bucket] += card.quantity;
+    }
+    let nonland = stats.creatures + stats.spells;
+    if nonland > 0 {
+        stats.average_cmc = cmc_total / nonland as f64;
+    }
+    stats
+}
+
+/// counts a color in a mana cost like {2}{W}{W/U}, hybrid and phyrexian symbols count for
+/// every color in them
+fn count_pips(mana_cost: &str, pip: char) -> u64 {
+    let pip = pip.to_string();
+    mana_cost
+        .split(['{', '}'])
+        .filter(|symbol| symbol.split('/').any(|s| s == pip))
+        .count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::scryfall::{make_safe_name, ScryfallCardFace};
+
+    fn face(name: &str, mana_cost: &str, type_line: &str) -> ScryfallCardFace {
+        ScryfallCardFace {
+            name: name.to_string(),
+            mana_cost: mana_cost.to_string(),
+            type_line: Some(type_line.to_string()),
+            oracle_text: String::new(),
+            colors: None,
+        }
+    }
+
+    #[test]
+    fn pips_from_front_face() {
+        let mut dfc = ScryfallCard::test_card(
+            "Delver of Secrets // Insectile Aberration",
+            "Creature — Human Wizard // Creature — Human Insect",
+            "",
+            "",
+        );
+        dfc.card_faces = vec![
+            face("Delver of Secrets", "{U}", "Creature — Human Wizard"),
+            face("Insectile Aberration", "", "Creature — Human Insect"),
+        ];
+        let bolt = ScryfallCard::test_card("Lightning Bolt", "Instant", "{R}", "");
+        let database: HashMap<String, ScryfallCard> = [dfc, bolt]
+            .into_iter()
+            .map(|c| (make_safe_name(&c.name, true), c))
+            .collect();
+        let decklist: Vec<CollectionCard> = [("Delver of Secrets", 4), ("Lightning Bolt", 3)]
+            .into_iter()
+            .map(|(name, quantity)| CollectionCard {
+                name: name.to_string(),
+                quantity,
+                ..Default::default()
+            })
+            .collect();
+        let stats = async_std::task::block_on(deck_stats(&decklist, &database));
+        assert_eq!(stats.not_found, 0);
+        assert_eq!(stats.pips, [0, 4, 0, 3, 0, 0]);
+        assert_eq!(stats.creatures, 4);
+    }
+
+    #[test]
+    fn hybrid_and_phyrexian_pips() {
+        assert_eq!(count_pips("{2}{W}{W/U}", 'W'), 2);
+        assert_eq!(count_pips("{2}{W}{W/U}", 'U'), 1);
+        assert_eq!(count_pips("{G/P}{C}", 'G'), 1);
+        assert_eq!(count_pips("", 'B'), 0);
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    collection::{CollectionCard, FormatLegal},
+    database::scryfall::{
+        get_min_price, make_safe_name, match_card, GameFormat, Legality, MtGColors, PriceType,
+        ScryfallCard,
+    },
+    missing::type_rank,
+};
+
+/// most substitutes suggested for a card
+pub const MAX_SUBSTITUTES: usize = 5;
+
+/// formats a deck's target format is picked from when config.toml doesn't set one, most
+/// restrictive first
+const TARGET_FORMATS: [&str; 7] = [
+    "pauper",
+    "standard",
+    "pioneer",
+    "modern",
+    "legacy",
+    "vintage",
+    "commander",
+];
+
+/// share of oracle text words two cards need in common when they have no keyword in common
+const MIN_TEXT_OVERLAP: f64 = 0.3;
+
+/// a cheaper card that does a similar job
+#[derive(Clone, Debug)]
+pub struct Substitute {
+    pub name: String,
+    pub unit_price: f64,
+    pub owned: u64,
+    pub savings: f64, // for every missing copy, owned copies cost nothing
+}
+
+/// the format substitutes have to be legal in, either the one set in config.toml or the most
+/// restrictive format the deck is legal in
+pub fn target_format(
+    config_format: Option<&str>,
+    legality: Option<&FormatLegal>,
+) -> Option<String> {
+    if let Some(format) = config_format {
+        return Some(format.to_lowercase());
+    }
+    let legality = legality?;
+    TARGET_FORMATS
+        .into_iter()
+        .find(|f| legality.get(f) == Some(true))
+        .map(|f| f.to_string())
+}
+
+/// cards from the database that could replace a missing card for less money
+/// candidates have the same card type, a mana value within 1, no colors the original doesn't
+/// have and a keyword or a good part of their oracle text in common, and are either owned or
+/// cheaper, best matches first
+/// with a digital collection, only cards in its game are suggested
+pub fn find_substitutes(
+    card: &CollectionCard,
+    database: &HashMap<String, ScryfallCard>,
+    collection: &[CollectionCard],
+    currency: &PriceType,
+    format: Option<&str>,
+    game: Option<GameFormat>,
+) -> Vec<Substitute> {
+    let original = match match_card(&card.name, database) {
+        Some(m) => m,
+        None => return Vec::new(),
+    };
+    let price = get_min_price(std::slice::from_ref(&original), currency.clone());
+    let category = type_rank(Some(&original));
+    let cmc = original.cmc.unwrap_or(0.0);
+    let colors = original.color_identity.clone().unwrap_or_default();
+    let keywords: HashSet<&String> = original.keywords.iter().collect();
+    let words = text_words(&original);
+    let mut owned: HashMap<String, u64> = HashMap::new();
+    for c in collection {
+        *owned.entry(make_safe_name(&c.name, true)).or_insert(0) += c.quantity;
+    }
+    let mut scored: Vec<(f64, Substitute)> = Vec::new();
+    for (safe_name, candidate) in database {
+        if candidate.name == original.name
+            || candidate.name.starts_with("A-")
+            || type_rank(Some(candidate)) != category
+            || (candidate.cmc.unwrap_or(0.0) - cmc).abs() > 1.0
+            || !same_colors(&colors, &candidate.color_identity)
+            || game.is_some_and(|g| !candidate.in_game(&g))
+        {
+            continue;
+        }
+        if let Some(f) = format {
+            if !matches!(
+                candidate.legalities.get(f),
+                Some(Legality::Legal | Legality::Restricted)
+            ) {
+                continue;
+            }
+        }
+        let owned = *owned.get(safe_name).unwrap_or(&0);
+        let unit_price = get_min_price(std::slice::from_ref(candidate), currency.clone());
+        if owned == 0 && (unit_price <= 0.0 || unit_price >= price) {
+            continue;
+        }
+        let shared_keywords = candidate
+            .keywords
+            .iter()
+            .filter(|k| keywords.contains(k))
+            .count();
+        let overlap = text_overlap(&words, &text_words(candidate));
+        if shared_keywords == 0 && overlap < MIN_TEXT_OVERLAP {
+            continue;
+        }
+        let buy = card.quantity.saturating_sub(owned);
+        let savings = price * card.quantity as f64 - unit_price * buy as f64;
+        scored.push((
+            shared_keywords as f64 + overlap * 2.0,
+            Substitute {
+                name: candidate.name.clone(),
+                unit_price,
+                owned,
+                savings,
+            },
+        ));
+    }
+    scored.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| b.1.savings.total_cmp(&a.1.savings))
+            .then_with(|| a.1.name.cmp(&b.1.name))
+    });
+    scored
+        .into_iter()
+        .take(MAX_SUBSTITUTES)
+        .map(|(_, s)| s)
+        .collect()
+}
+
+/// the candidate's colors all fit in the original's color identity
+fn same_colors(colors: &[MtGColors], candidate: &Option<Vec<MtGColors>>) -> bool {
+    candidate
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .all(|c| colors.contains(c))
+}
+
+/// the words of the oracle text that say what a card does, with the card's own name left out,
+/// every face counts for double faced cards
+fn text_words(card: &ScryfallCard) -> HashSet<String> {
+    const COMMON: [&str; 12] = [
+        "the", "a", "an", "of", "to", "and", "or", "you", "your", "its", "it", "that",
+    ];
+    let mut text = card.face_oracle_text().replace(&card.name, "");
+    for face in card.card_faces.iter() {
+        text = text.replace(&face.name, "");
+    }
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '+' && c != '/')
+        .filter(|w| !w.is_empty() && !COMMON.contains(w))
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// shared words over all words
+fn text_overlap(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
@@ -0,0 +1,200 @@
+use std::{collections::HashMap, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collection::CollectionCard,
+    database::{
+        scryfall::{get_min_price, make_safe_name, match_card, PriceType, ScryfallCard},
+        sets::{owned_printing, ScryfallSet},
+    },
+};
+
+/// number of copies of a card kept out of the trade binder
+pub const PLAYSET: u64 = 4;
+
+/// file formats the trade list can be exported in
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub enum TradeFormat {
+    Moxfield,
+    Deckbox,
+    #[default]
+    Text,
+}
+
+impl TradeFormat {
+    /// name of the exported file
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            TradeFormat::Moxfield => "trade_list_moxfield.csv",
+            TradeFormat::Deckbox => "trade_list_deckbox.csv",
+            TradeFormat::Text => "trade_list.txt",
+        }
+    }
+}
+
+impl FromStr for TradeFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "moxfield" => Ok(TradeFormat::Moxfield),
+            "deckbox" => Ok(TradeFormat::Deckbox),
+            "text" | "txt" => Ok(TradeFormat::Text),
+            _ => Err(format!("Unknown trade list format: {}", s)),
+        }
+    }
+}
+
+/// surplus copies of a single printing that can go in the trade binder
+#[derive(Clone, Debug)]
+pub struct TradeCard {
+    pub name: String,
+    pub quantity: u64,
+    pub edition: String,
+    pub set_name: String,
+    pub collector_number: String,
+    pub unit_price: f64,
+}
+
+impl TradeCard {
+    pub fn total(&self) -> f64 {
+        self.unit_price * self.quantity as f64
+    }
+}
+
+/// finds copies in the collection beyond a playset (or a single copy for singleton formats),
+/// minus the copies used by saved decks
+/// each printing is priced on its own, printings worth less than min_value each and basic lands are left out
+/// printings is the collection before squashing so the trade list can keep track of editions
+pub async fn find_surplus(
+    printings: &[CollectionCard],
+    decks: &[Vec<CollectionCard>],
+    database: &HashMap<String, ScryfallCard>,
+    sets: &HashMap<String, ScryfallSet>,
+    currency: PriceType,
+    singleton: bool,
+    min_value: f64,
+) -> Vec<TradeCard> {
+    let keep = if singleton { 1 } else { PLAYSET };
+    // copies each deck needs
+    let mut allocated: HashMap<String, u64> = HashMap::new();
+    for deck in decks {
+        for card in deck {
+            *allocated
+                .entry(make_safe_name(&card.name, true))
+                .or_insert(0) += card.quantity;
+        }
+    }
+    // group printings by card, keeping the order they appear in the collection
+    let mut owned: Vec<(String, Vec<&CollectionCard>)> = Vec::new();
+    let mut owned_index: HashMap<String, usize> = HashMap::new();
+    for printing in printings {
+        let safe_name = make_safe_name(&printing.name, true);
+        match owned_index.get(&safe_name) {
+            Some(i) => owned[*i].1.push(printing),
+            None => {
+                owned_index.insert(safe_name.clone(), owned.len());
+                owned.push((safe_name, vec![printing]));
+            }
+        }
+    }
+    let mut trade_cards = Vec::new();
+    for (safe_name, mut group) in owned {
+        let matched = match match_card(&safe_name, database) {
+            Some(m) => m,
+            None => continue,
+        };
+        if let Some(type_line) = &matched.type_line {
+            if type_line.contains("Basic Land") {
+                continue;
+            }
+        }
+        let cheapest = get_min_price(std::slice::from_ref(&matched), currency.clone());
+        let total: u64 = group.iter().map(|p| p.quantity).sum();
+        let used = keep + allocated.get(&safe_name).unwrap_or(&0);
+        let mut surplus = total.saturating_sub(used);
+        // trade away the printings with the most copies first
+        group.sort_by_key(|p| std::cmp::Reverse(p.quantity));
+        for printing in group {
+            if surplus == 0 {
+                break;
+            }
+            let quantity = surplus.min(printing.quantity);
+            surplus -= quantity;
+            let unit_price = owned_printing(&printing.edition, &printing.collector_number, sets)
+                .and_then(|p| p.prices.get_finish(&currency, &printing.foil))
+                .unwrap_or(cheapest);
+            if unit_price < min_value {
+                continue;
+            }
+            let set_name = match sets.get(&printing.edition.to_lowercase()) {
+                Some(set) => set.name.clone(),
+                None if printing.edition.eq_ignore_ascii_case(&matched.set) => {
+                    matched.set_name.clone()
+                }
+                None => String::new(),
+            };
+            trade_cards.push(TradeCard {
+                name: printing.name.clone(),
+                quantity,
+                edition: printing.edition.clone(),
+                set_name,
+                collector_number: printing.collector_number.clone(),
+                unit_price,
+            });
+        }
+    }
+    trade_cards.sort_by(|a, b| b.total().total_cmp(&a.total()));
+    trade_cards
+}
+
+/// builds the text of the trade list in the requested format
+pub fn trade_list_string(cards: &[TradeCard], format: &TradeFormat) -> String {
+    let mut list = String::new();
+    match format {
+        TradeFormat::Moxfield => {
+            list += "\"Count\",\"Tradelist Count\",\"Name\",\"Edition\",\"Collector Number\"\n";
+            for card in cards {
+                list += &format!(
+                    "\"{}\",\"{}\",{},\"{}\",\"{}\"\n",
+                    card.quantity,
+                    card.quantity,
+                    csv_quote(&card.name),
+                    card.edition,
+                    card.collector_number
+                );
+            }
+        }
+        TradeFormat::Deckbox => {
+            list += "Count,Tradelist Count,Name,Edition,Card Number\n";
+            for card in cards {
+                // Deckbox matches editions by their full name
+                let edition = if card.set_name.is_empty() {
+                    card.edition.to_uppercase()
+                } else {
+                    card.set_name.clone()
+                };
+                list += &format!(
+                    "{},{},{},{},{}\n",
+                    card.quantity,
+                    card.quantity,
+                    csv_quote(&card.name),
+                    csv_quote(&edition),
+                    card.collector_number
+                );
+            }
+        }
+        TradeFormat::Text => {
+            for card in cards {
+                list += &format!("{} {}\n", card.quantity, card.name);
+            }
+        }
+    }
+    list
+}
+
+/// wraps a CSV field in quotes, escaping any quotes inside it
+fn csv_quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
@@ -0,0 +1,303 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, Borders, Clear, List, ListState},
+    Frame,
+};
+
+use crate::{database::scryfall::make_safe_name, search::normalize};
+
+/// most suggestions shown under an input
+pub const MAX_SUGGESTIONS: usize = 8;
+
+/// characters typed before suggestions show up
+const MIN_FRAGMENT: usize = 2;
+
+/// what an input did with a key press
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputAction {
+    Edited,    // the text changed, the suggestions need updating
+    Completed, // the name being typed was replaced with the highlighted suggestion
+    Submitted, // Enter
+    Cancelled, // Esc
+    Ignored,
+}
+
+/// card names with their normalized faces, built once so typing doesn't normalize every name
+#[derive(Debug, Clone, Default)]
+pub struct NameIndex {
+    names: Vec<(String, Vec<String>)>,
+}
+
+impl NameIndex {
+    pub fn new<'a>(names: impl Iterator<Item = &'a str>) -> Self {
+        let mut names: Vec<(String, Vec<String>)> = names
+            .map(|name| {
+                let faces = normalize(name)
+                    .split("//")
+                    .map(|f| f.trim().to_string())
+                    .collect();
+                (name.to_string(), faces)
+            })
+            .collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        names.dedup_by(|a, b| a.0 == b.0);
+        NameIndex { names }
+    }
+}
+
+/// text input with card name suggestions, used wherever card names are typed
+#[derive(Debug, Clone, Default)]
+pub struct NameInput {
+    pub text: String,
+    pub suggestions: Vec<String>,
+    pub selected: usize,
+    query_syntax: bool, // only the plain words at the end of a query are a card name
+    quote: bool,        // completed names are wrapped in quotes
+}
+
+impl NameInput {
+    /// the whole text is a card name
+    pub fn name() -> Self {
+        NameInput::default()
+    }
+
+    /// the text is a query, the name being typed is either after an unclosed quote or the
+    /// plain words (no keywords or operators) at the end
+    pub fn query(quote: bool) -> Self {
+        NameInput {
+            query_syntax: true,
+            quote,
+            ..Default::default()
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.suggestions.clear();
+        self.selected = 0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// the highlighted suggestion
+    pub fn selected_name(&self) -> Option<&str> {
+        self.suggestions.get(self.selected).map(|s| s.as_str())
+    }
+
+    /// handles a key press, Tab completes the highlighted suggestion and Up/Down pick one
+    pub fn handle_key(&mut self, code: KeyCode) -> InputAction {
+        match code {
+            KeyCode::Char(c) => {
+                self.text.push(c);
+                InputAction::Edited
+            }
+            KeyCode::Backspace => {
+                self.text.pop();
+                InputAction::Edited
+            }
+            KeyCode::Up if !self.suggestions.is_empty() => {
+                self.selected = self.selected.saturating_sub(1);
+                InputAction::Ignored
+            }
+            KeyCode::Down if self.selected + 1 < self.suggestions.len() => {
+                self.selected += 1;
+                InputAction::Ignored
+            }
+            KeyCode::Tab => match self.selected_name().map(|s| s.to_string()) {
+                Some(name) => {
+                    self.complete(&name);
+                    InputAction::Completed
+                }
+                None => InputAction::Ignored,
+            },
+            KeyCode::Enter => InputAction::Submitted,
+            KeyCode::Esc => InputAction::Cancelled,
+            _ => InputAction::Ignored,
+        }
+    }
+
+    /// finds the names that match the name being typed
+    pub fn update(&mut self, index: &NameIndex) {
+        self.suggestions = suggest(self.fragment(), index);
+        self.selected = 0;
+    }
+
+    /// the part of the text that is a card name
+    fn fragment(&self) -> &str {
+        self.text[self.fragment_start()..].trim()
+    }
+
+    fn fragment_start(&self) -> usize {
+        if !self.query_syntax {
+            return 0;
+        }
+        if self.text.matches('"').count() % 2 == 1 {
+            return self.text.rfind('"').map(|i| i + 1).unwrap_or(0);
+        }
+        let mut start = self.text.len();
+        let mut end = self.text.len();
+        for word in self.text.rsplit(' ') {
+            let word_start = end - word.len();
+            if !word.is_empty() && !is_plain_word(word) {
+                break;
+            }
+            start = word_start;
+            end = word_start.saturating_sub(1); // the space before the word
+        }
+        start
+    }
+
+    /// replaces the name being typed with a full card name
+    fn complete(&mut self, name: &str) {
+        let mut text = self.text[..self.fragment_start()].to_string();
+        if self.quote {
+            // an unclosed quote is replaced along with the name
+            if text.ends_with('"') {
+                text.pop();
+            }
+            text += &format!("\"{}\"", name);
+        } else {
+            text += name;
+        }
+        self.text = text;
+        self.suggestions.clear();
+        self.selected = 0;
+    }
+
+    /// draws the suggestions in the given area, over anything already there
+    pub fn draw_suggestions(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = self
+            .suggestions
+            .iter()
+            .map(|name| Line::from(name.clone()))
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(self.selected));
+        let list = List::new(lines)
+            .block(
+                Block::default()
+                    .title("| Suggestions <Tab> |")
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(Style::default().reversed());
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    /// an area for the suggestions just below (or above) a line of the screen, clipped to the
+    /// bounds area
+    pub fn popup_area(&self, line: Rect, bounds: Rect, below: bool) -> Rect {
+        let height = (self.suggestions.len() as u16 + 2).min(bounds.height);
+        let width = self
+            .suggestions
+            .iter()
+            .map(|s| s.chars().count() as u16 + 4)
+            .max()
+            .unwrap_or(0)
+            .max(26)
+            .min(bounds.width);
+        let y = if below {
+            (line.y + line.height).min(bounds.bottom().saturating_sub(height))
+        } else {
+            line.y.saturating_sub(height).max(bounds.y)
+        };
+        Rect::new(line.x.max(bounds.x), y, width, height)
+    }
+}
+
+/// words that can't be part of a card name in a query: keywords, comparisons and negations
+fn is_plain_word(word: &str) -> bool {
+    !word.starts_with('-') && !word.contains([':', '<', '>', '=', '!', '"'])
+}
+
+/// the names that best match the typed text, handling split and double faced cards the way
+/// make_safe_name() does so either face can be typed
+pub fn suggest(fragment: &str, index: &NameIndex) -> Vec<String> {
+    let fragment = normalize(&make_safe_name(fragment, true));
+    if fragment.chars().count() < MIN_FRAGMENT {
+        return Vec::new();
+    }
+    let typed: Vec<char> = fragment.chars().collect();
+    let mut scored: Vec<((u8, usize), &str)> = index
+        .names
+        .iter()
+        .filter_map(|(name, faces)| {
+            let tier = faces
+                .iter()
+                .filter_map(|face| face_tier(&fragment, &typed, face))
+                .min()?;
+            Some(((tier, name.len()), name.as_str()))
+        })
+        .collect();
+    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// how well a face matches, lower is better, None if it doesn't match
+/// exact, prefix, start of a word, anywhere, letters in order, then a prefix with a typo
+fn face_tier(fragment: &str, typed: &[char], face: &str) -> Option<u8> {
+    if face == fragment {
+        Some(0)
+    } else if face.starts_with(fragment) {
+        Some(1)
+    } else if face
+        .split([' ', '-'])
+        .any(|word| word.starts_with(fragment))
+    {
+        Some(2)
+    } else if face.contains(fragment) {
+        Some(3)
+    } else if is_subsequence(fragment, face) {
+        Some(4)
+    } else if is_typo(typed, face) {
+        Some(5)
+    } else {
+        None
+    }
+}
+
+/// every typed letter appears in order, e.g. "lghtnbolt"
+fn is_subsequence(fragment: &str, face: &str) -> bool {
+    let mut chars = face.chars();
+    fragment
+        .chars()
+        .filter(|c| *c != ' ')
+        .all(|c| chars.any(|f| f == c))
+}
+
+/// the start of the name is within a few typos of the typed text, about 1 per 4 letters
+/// only names starting with the same letter and long enough are checked, edit distance is slow
+fn is_typo(typed: &[char], face: &str) -> bool {
+    if typed.len() < 4 {
+        return false;
+    }
+    let typos = typed.len() / 4;
+    if !face.starts_with(typed[0]) || face.chars().count() + typos < typed.len() {
+        return false;
+    }
+    let prefix: Vec<char> = face.chars().take(typed.len()).collect();
+    edit_distance(typed, &prefix) <= typos
+}
+
+/// Levenshtein distance
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitute.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -1,6 +1,7 @@
 use std::io::{self, stdout, Stdout};
 
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -10,14 +11,31 @@ use ratatui::{
     style::{Style, Stylize},
     symbols::border,
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, Tabs, Wrap},
+    widgets::{
+        BarChart, Block, Borders, List, Paragraph, Scrollbar, ScrollbarOrientation, Sparkline,
+        Tabs, Wrap,
+    },
     Frame, Terminal,
 };
 use ratatui_explorer::FileExplorer;
 
-use crate::{app::App, database::scryfall::PriceType};
+use crate::{
+    app::{App, DeckInputMode},
+    budget::{budget_plan, over_threshold, unit_prices, BudgetPlan},
+    collection::{main_deck, CollectionSource, DeckSection},
+    commander::{CommanderCheck, DECK_SIZE},
+    database::scryfall::{format_label, make_safe_name, PriceType},
+    keybindings::Action,
+    odds::prob_by_turn,
+    stats::{CURVE_BUCKETS, PIP_SYMBOLS},
+    substitute::MAX_SUBSTITUTES,
+    tui::theme::Palette,
+    value::{CollectionValue, DEFAULT_TOP_NUM},
+    wildcard::{is_alchemy, wildcard_total, WildcardCost},
+};
 
 use super::{
+    detail::draw_card_detail,
     help::{ABOUT_STR, BUG_STR, HELP_STR},
     space_padding,
 };
@@ -35,19 +53,67 @@ pub enum MenuTabs {
     Deck,
     Missing,
     Help,
+    Value,
+    Sets,
+    Search,
     Debug,
 }
 
+/// titles on the tab bar, in MenuTabs order (Debug isn't on the bar)
+pub const TAB_TITLES: [&str; 9] = [
+    "1. Welcome",
+    "2. Database",
+    "3. Collection",
+    "4. Deck",
+    "5. Missing",
+    "6. Help",
+    "7. Value",
+    "8. Sets",
+    "9. Search",
+];
+
+impl MenuTabs {
+    /// the tab at a position on the tab bar
+    pub fn from_index(index: usize) -> Option<MenuTabs> {
+        match index {
+            0 => Some(MenuTabs::Welcome),
+            1 => Some(MenuTabs::Database),
+            2 => Some(MenuTabs::Collection),
+            3 => Some(MenuTabs::Deck),
+            4 => Some(MenuTabs::Missing),
+            5 => Some(MenuTabs::Help),
+            6 => Some(MenuTabs::Value),
+            7 => Some(MenuTabs::Sets),
+            8 => Some(MenuTabs::Search),
+            _ => None,
+        }
+    }
+}
+
+/// the tab under a column of the tab bar
+/// the bar has a border, and each title is padded by a space on both sides with a divider between
+pub fn tab_at(tabs_area: Rect, column: u16) -> Option<MenuTabs> {
+    let mut start = tabs_area.x + 1;
+    for (i, title) in TAB_TITLES.iter().enumerate() {
+        let end = start + title.chars().count() as u16 + 2;
+        if column >= start && column < end {
+            return MenuTabs::from_index(i);
+        }
+        start = end + 1;
+    }
+    None
+}
+
 /// initialize the terminal
 pub fn init() -> io::Result<Tui> {
-    execute!(stdout(), EnterAlternateScreen)?;
+    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
     enable_raw_mode()?;
     Terminal::new(CrosstermBackend::new(stdout()))
 }
 
 /// restore the terminal to it's original state
 pub fn restore() -> io::Result<()> {
-    execute!(stdout(), LeaveAlternateScreen)?;
+    execute!(stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
     disable_raw_mode()?;
     Ok(())
 }
@@ -60,6 +126,7 @@ pub fn ui(
     explorer2: &mut FileExplorer,
     database_explorer: &mut FileExplorer,
 ) {
+    let p = app.config.palette.palette();
     // split area into 3 chunks (tabs/main/keys)
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -69,24 +136,20 @@ pub fn ui(
             Constraint::Length(3),
         ])
         .split(frame.area());
+    // saved for mouse clicks, the list is set by the tabs that have one
+    app.tabs_area = chunks[0];
+    app.list_area = Rect::default();
 
     // tabs for switching between menus
-    let tabs = Tabs::new(vec![
-        "1. Welcome",
-        "2. Database",
-        "3. Collection",
-        "4. Deck",
-        "5. Missing",
-        "6. Help",
-    ])
-    .block(Block::default().title("| Menu |").borders(Borders::ALL))
-    .style(Style::default().white())
-    .highlight_style(Style::default().cyan().bold())
-    .select(app.active_tab as usize);
+    let tabs = Tabs::new(TAB_TITLES)
+        .block(Block::default().title("| Menu |").borders(Borders::ALL))
+        .style(Style::default().white())
+        .highlight_style(Style::default().fg(p.info).bold())
+        .select(app.active_tab as usize);
 
     // define main/center area for display
     let version =
-        Line::from(vec!["| Decklist v0.5.1 |".into()]).style(Style::default().cyan().bold());
+        Line::from(vec!["| Decklist v0.5.1 |".into()]).style(Style::default().fg(p.info).bold());
     let main_block = Block::default()
         .title_bottom(version)
         .title_alignment(Alignment::Center)
@@ -96,85 +159,212 @@ pub fn ui(
     // change bottom two chunks based on selected tab
     let instructions_block = Block::default().borders(Borders::ALL);
     let mut instructions_text = Text::from(vec![Line::from(vec![
-        "<Q>".yellow().bold(),
+        key_hint(app, &[Action::Quit]),
         " Quit ".into(),
-        "<1-6>".yellow().bold(),
+        tab_hint(app),
         " Change Tabs ".into(),
     ])]);
+    let list_query_active = app.list_query_tab == app.active_tab
+        && (app.list_query_editing || !app.list_query_input.is_empty());
     match app.active_tab {
         MenuTabs::Welcome => {
             draw_welcome_main(app, frame, chunks[1], main_block);
         }
         MenuTabs::Database => {
             instructions_text = Text::from(vec![Line::from(vec![
-                "<Q>".yellow().bold(),
+                key_hint(app, &[Action::Quit]),
                 " Quit ".into(),
-                "<S>".yellow().bold(),
+                key_hint(app, &[Action::Load]),
                 " Load file ".into(),
-                "<Up/Down>".yellow().bold(),
+                key_hint(app, &[Action::Up, Action::Down]),
                 " Navigate ".into(),
-                "<Left/Backspace>".yellow().bold(),
+                "<Left/Backspace>".fg(p.key).bold(),
                 " Exit Directory ".into(),
-                "<Right/Enter>".yellow().bold(),
+                "<Right/Enter>".fg(p.key).bold(),
                 " Down Directory ".into(),
             ])]);
             draw_database_main(app, frame, chunks[1], main_block, database_explorer);
         }
         MenuTabs::Collection => {
             instructions_text = Text::from(vec![Line::from(vec![
-                "<Q>".yellow().bold(),
+                key_hint(app, &[Action::Quit]),
                 " Quit ".into(),
-                "<S>".yellow().bold(),
+                key_hint(app, &[Action::Load]),
                 " Load file ".into(),
-                "<Esc>".yellow().bold(),
+                key_hint(app, &[Action::Back]),
                 " Reset file ".into(),
-                "<Up/Down>".yellow().bold(),
+                key_hint(app, &[Action::Up, Action::Down]),
                 " Navigate ".into(),
-                "<Left/Backspace>".yellow().bold(),
+                key_hint(app, &[Action::ListSearch]),
+                " Search ".into(),
+                "<Left/Backspace>".fg(p.key).bold(),
                 " Exit Directory ".into(),
-                "<Right/Enter>".yellow().bold(),
+                "<Right/Enter>".fg(p.key).bold(),
                 " Down Directory ".into(),
             ])]);
             draw_collection_main(app, frame, chunks[1], main_block, explorer);
         }
+        MenuTabs::Deck if app.deck_input_mode.is_some() => {
+            let enter_text = match app.deck_input_mode {
+                Some(DeckInputMode::Rename) => " Rename ",
+                Some(DeckInputMode::SaveAs) => " Save ",
+                _ => " Add to main deck ",
+            };
+            instructions_text = Text::from(vec![Line::from(vec![
+                "<Up/Down>".fg(p.key).bold(),
+                " Choose ".into(),
+                "<Tab>".fg(p.key).bold(),
+                " Complete ".into(),
+                "<Enter>".fg(p.key).bold(),
+                enter_text.into(),
+                "<Esc>".fg(p.key).bold(),
+                " Cancel ".into(),
+            ])]);
+            draw_decklist_main(app, frame, chunks[1], main_block, explorer2);
+        }
+        MenuTabs::Deck if app.deck_editing => {
+            instructions_text = Text::from(vec![Line::from(vec![
+                key_hint(app, &[Action::Quit]),
+                " Quit ".into(),
+                key_hint(app, &[Action::Up, Action::Down]),
+                " Navigate ".into(),
+                "<+/->".fg(p.key).bold(),
+                " Quantity ".into(),
+                "<M>".fg(p.key).bold(),
+                " Main/Sideboard ".into(),
+                "<A>".fg(p.key).bold(),
+                " Add card ".into(),
+                "<R>".fg(p.key).bold(),
+                " Fix name ".into(),
+                "<W>".fg(p.key).bold(),
+                " Save ".into(),
+                "<Esc/E>".fg(p.key).bold(),
+                " Stop editing ".into(),
+            ])]);
+            draw_decklist_main(app, frame, chunks[1], main_block, explorer2);
+        }
         MenuTabs::Deck => {
             instructions_text = Text::from(vec![Line::from(vec![
-                "<Q>".yellow().bold(),
+                key_hint(app, &[Action::Quit]),
                 " Quit ".into(),
-                "<S>".yellow().bold(),
+                key_hint(app, &[Action::Load]),
                 " Load file ".into(),
-                "<Esc>".yellow().bold(),
+                key_hint(app, &[Action::Back]),
                 " Reset file ".into(),
-                "<Up/Down>".yellow().bold(),
+                key_hint(app, &[Action::Up, Action::Down]),
                 " Navigate ".into(),
-                "<Left/Backspace>".yellow().bold(),
+                key_hint(app, &[Action::ListSearch]),
+                " Search ".into(),
+                key_hint(app, &[Action::Toggle]),
+                " Toggle stats ".into(),
+                key_hint(app, &[Action::SampleHand]),
+                " Sample hand ".into(),
+                key_hint(app, &[Action::Compare]),
+                " Compare versions ".into(),
+                key_hint(app, &[Action::Edit]),
+                " Edit ".into(),
+                "<Left/Backspace>".fg(p.key).bold(),
                 " Exit Directory ".into(),
-                "<Right/Enter>".yellow().bold(),
+                "<Right/Enter>".fg(p.key).bold(),
                 " Down Directory ".into(),
             ])]);
             draw_decklist_main(app, frame, chunks[1], main_block, explorer2);
         }
         MenuTabs::Missing => {
             instructions_text = Text::from(vec![Line::from(vec![
-                "<Q>".yellow().bold(),
+                key_hint(app, &[Action::Quit]),
                 " Quit ".into(),
-                "<C>".yellow().bold(),
+                key_hint(app, &[Action::Copy]),
                 " Copy to clipboard ".into(),
-                "<F>".yellow().bold(),
+                key_hint(app, &[Action::SaveFile]),
                 " Save to file ".into(),
-                "<Up/Down>".yellow().bold(),
+                key_hint(app, &[Action::Report]),
+                " Shopping report ".into(),
+                key_hint(app, &[Action::Sort]),
+                format!(" Sort: {} ", app.missing_sort.label()).into(),
+                key_hint(app, &[Action::Toggle]),
+                format!(" Format: {} ", app.config.missing_format.label()).into(),
+                key_hint(app, &[Action::Currency]),
+                match app.collection_source {
+                    CollectionSource::Mtgo => " Currency: Tix (MTGO) ".into(),
+                    _ => format!(" Currency: {} ", app.currency().symbol().trim()).into(),
+                },
+                key_hint(app, &[Action::PriceColumns]),
+                " USD/EUR/Tix ".into(),
+                key_hint(app, &[Action::Wildcards]),
+                " Wildcards ".into(),
+                key_hint(app, &[Action::Up, Action::Down]),
                 " Navigate ".into(),
+                key_hint(app, &[Action::ListSearch]),
+                " Search ".into(),
             ])]);
             draw_missing_main(app, frame, chunks[1], main_block);
         }
         MenuTabs::Help => {
-            draw_help_main(frame, chunks[1], main_block);
+            draw_help_main(app, frame, chunks[1], main_block);
+        }
+        MenuTabs::Value => {
+            instructions_text = Text::from(vec![Line::from(vec![
+                key_hint(app, &[Action::Quit]),
+                " Quit ".into(),
+                key_hint(app, &[Action::SaveFile]),
+                " Save to file ".into(),
+                key_hint(app, &[Action::Toggle]),
+                " Toggle trade list ".into(),
+                key_hint(app, &[Action::Up, Action::Down]),
+                " Scroll ".into(),
+            ])]);
+            if app.trade_view {
+                draw_trade_main(app, frame, chunks[1], main_block);
+            } else {
+                draw_value_main(app, frame, chunks[1], main_block);
+            }
+        }
+        MenuTabs::Sets => {
+            instructions_text = Text::from(vec![Line::from(vec![
+                key_hint(app, &[Action::Quit]),
+                " Quit ".into(),
+                key_hint(app, &[Action::Rarity]),
+                " Change rarity ".into(),
+                key_hint(app, &[Action::Up, Action::Down]),
+                " Select set ".into(),
+                key_hint(app, &[Action::Left, Action::Right]),
+                " Scroll missing ".into(),
+            ])]);
+            draw_sets_main(app, frame, chunks[1], main_block);
+        }
+        MenuTabs::Search => {
+            instructions_text = if app.search_editing {
+                Text::from(vec![Line::from(vec![
+                    "<Enter>".fg(p.key).bold(),
+                    " Search ".into(),
+                    "<Esc>".fg(p.key).bold(),
+                    " Stop typing ".into(),
+                ])])
+            } else {
+                Text::from(vec![Line::from(vec![
+                    key_hint(app, &[Action::Quit]),
+                    " Quit ".into(),
+                    key_hint(app, &[Action::Select]),
+                    " Type search ".into(),
+                    key_hint(app, &[Action::Up, Action::Down]),
+                    " Scroll ".into(),
+                ])])
+            };
+            draw_search_main(app, frame, chunks[1], main_block);
         }
         MenuTabs::Debug => {
             draw_debug_main(app, frame, chunks[1], main_block);
         }
     }
 
+    if list_query_active {
+        instructions_text = list_query_text(app);
+        if app.list_query_editing && !app.list_query_input.suggestions.is_empty() {
+            let area = app.list_query_input.popup_area(chunks[2], chunks[1], false);
+            app.list_query_input.draw_suggestions(frame, area);
+        }
+    }
     let instructions = Paragraph::new(instructions_text)
         .centered()
         .block(instructions_block);
@@ -185,6 +375,7 @@ pub fn ui(
 }
 
 fn draw_debug_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let p = app.config.palette.palette();
     let sections = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(75), Constraint::Percentage(25)])
@@ -194,47 +385,67 @@ fn draw_debug_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Bl
         Line::from(vec![
             Span::from("Directory Check: ").bold(),
             Span::from(space_padding(6)),
-            Span::from(format!("{}", app.directory_counter)).cyan(),
+            Span::from(format!("{}", app.directory_counter)).fg(p.info),
         ]),
         Line::from(vec![
             Span::from("Config Check: ").bold(),
             Span::from(space_padding(9)),
-            Span::from(format!("{}", app.config_counter)).cyan(),
+            Span::from(format!("{}", app.config_counter)).fg(p.info),
         ]),
         Line::from(vec![
             Span::from("Database Check: ").bold(),
             Span::from(space_padding(7)),
-            Span::from(format!("{}", app.database_counter)).cyan(),
+            Span::from(format!("{}", app.database_counter)).fg(p.info),
         ]),
         Line::from(vec![
             Span::from("Collection Check: ").bold(),
             Span::from(space_padding(5)),
-            Span::from(format!("{}", app.collection_counter)).cyan(),
+            Span::from(format!("{}", app.collection_counter)).fg(p.info),
         ]),
         Line::from(vec![
             Span::from("Decklist Check: ").bold(),
             Span::from(space_padding(7)),
-            Span::from(format!("{}", app.decklist_counter)).cyan(),
+            Span::from(format!("{}", app.decklist_counter)).fg(p.info),
         ]),
         Line::from(vec![
             Span::from("Legal Check: ").bold(),
             Span::from(space_padding(10)),
-            Span::from(format!("{}", app.legal_counter)).cyan(),
+            Span::from(format!("{}", app.legal_counter)).fg(p.info),
         ]),
         Line::from(vec![
             Span::from("Missing Check: ").bold(),
             Span::from(space_padding(8)),
-            Span::from(format!("{}", app.missing_counter)).cyan(),
+            Span::from(format!("{}", app.missing_counter)).fg(p.info),
         ]),
         Line::from(vec![
             Span::from("Price Check: ").bold(),
             Span::from(space_padding(10)),
-            Span::from(format!("{}", app.price_counter)).cyan(),
+            Span::from(format!("{}", app.price_counter)).fg(p.info),
         ]),
         Line::from(vec![
             Span::from("Short Check: ").bold(),
             Span::from(space_padding(10)),
-            Span::from(format!("{}", app.short_counter)).cyan(),
+            Span::from(format!("{}", app.short_counter)).fg(p.info),
+        ]),
+        Line::from(vec![
+            Span::from("Value Check: ").bold(),
+            Span::from(space_padding(10)),
+            Span::from(format!("{}", app.value_counter)).fg(p.info),
+        ]),
+        Line::from(vec![
+            Span::from("Sets Check: ").bold(),
+            Span::from(space_padding(11)),
+            Span::from(format!("{}", app.sets_counter)).fg(p.info),
+        ]),
+        Line::from(vec![
+            Span::from("Search Check: ").bold(),
+            Span::from(space_padding(9)),
+            Span::from(format!("{}", app.search_counter)).fg(p.info),
+        ]),
+        Line::from(vec![
+            Span::from("Stats Check: ").bold(),
+            Span::from(space_padding(10)),
+            Span::from(format!("{}", app.stats_counter)).fg(p.info),
         ]),
     ]);
     frame.render_widget(main_block, chunk);
@@ -244,26 +455,27 @@ fn draw_debug_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Bl
 
 /// draw the main window on the welcome tab
 fn draw_welcome_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let p = app.config.palette.palette();
     // draw startup check status
     let directory_status_line = if app.directory_exist {
-        app.directory_status.clone().green()
+        app.directory_status.clone().fg(p.good)
     } else {
-        app.directory_status.clone().red()
+        app.directory_status.clone().fg(p.bad)
     };
     let config_status_line = if app.config_exist {
-        app.config_status.clone().green()
+        app.config_status.clone().fg(p.good)
     } else {
-        app.config_status.clone().red()
+        app.config_status.clone().fg(p.bad)
     };
     let database_status_line = if app.database_ok {
-        app.dc.database_status.clone().green()
+        app.dc.database_status.clone().fg(p.good)
     } else {
-        app.dc.database_status.clone().red()
+        app.dc.database_status.clone().fg(p.bad)
     };
     let collection_status_line = if app.collection_exist {
-        app.collection_status.clone().green()
+        app.collection_status.clone().fg(p.good)
     } else {
-        app.collection_status.clone().red()
+        app.collection_status.clone().fg(p.bad)
     };
     let directory_line = Line::from(vec!["Directory: ".into(), directory_status_line]);
     let config_line = Line::from(vec!["Config file: ".into(), config_status_line]);
@@ -317,13 +529,17 @@ fn draw_collection_main(
         .split(main_block.inner(chunk));
     if app.collection.is_some() && app.collection_file_name.is_some() {
         app.collection_status = format!(
-            "Collection loaded successfully.  Using {}",
-            app.collection_file_name.as_ref().unwrap() // NOTE: should exist if you get to this branch
+            "Collection loaded successfully.  Using {} ({} collection)",
+            app.collection_file_name.as_ref().unwrap(), // NOTE: should exist if you get to this branch
+            app.collection_source.label()
         );
     }
     let file_paragraph = if app.prompt_config_update {
         let words = app.collection_status.clone()
-            + "\nPress C to update config to auto load this collection file.";
+            + &format!(
+                "\nPress {} to update config to auto load this collection file.",
+                key_name(app, Action::Copy)
+            );
         Paragraph::new(words).wrap(Wrap { trim: true })
     } else {
         Paragraph::new(app.collection_status.clone()).wrap(Wrap { trim: true })
@@ -331,24 +547,44 @@ fn draw_collection_main(
     frame.render_widget(main_block, chunk);
     frame.render_widget(file_paragraph, sections[0]);
     if app.collection.is_some() {
+        let subs = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Fill(1), Constraint::Percentage(40)])
+            .split(sections[1]);
         let mut lines: Vec<Line> = Vec::new();
         for card in app.collection.as_ref().unwrap() {
             lines.push(Line::from(format!("{}", card)));
         }
+        mark_matches(app, &mut lines);
         app.collection_scroll_state = app.collection_scroll_state.content_length(lines.len());
-        let collection_paragraph = Paragraph::new(lines[app.collection_scroll..].to_vec());
+        app.collection_list_state
+            .select(Some(app.collection_scroll));
+        let collection_list = List::new(lines).highlight_style(Style::default().reversed());
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("^"))
             .end_symbol(Some("v"));
-        frame.render_widget(collection_paragraph, sections[1]);
+        frame.render_stateful_widget(collection_list, subs[0], &mut app.collection_list_state);
+        app.list_area = subs[0];
         frame.render_stateful_widget(
             scrollbar,
-            sections[1].inner(Margin {
+            subs[0].inner(Margin {
                 vertical: 0,
                 horizontal: 1,
             }),
             &mut app.collection_scroll_state,
         );
+        let selected = app
+            .collection
+            .as_ref()
+            .and_then(|cards| cards.get(app.collection_scroll))
+            .map(|c| c.name.as_str());
+        draw_card_detail(
+            frame,
+            subs[1],
+            selected,
+            &app.dc.database_cards,
+            app.config.palette.palette(),
+        );
     } else {
         frame.render_widget(&explorer.widget(), sections[1]);
     }
@@ -366,17 +602,47 @@ fn draw_decklist_main(
     main_block: Block,
     explorer: &mut FileExplorer,
 ) {
+    let p = app.config.palette.palette();
     // split into two sections - small one for info text and main for displaying file explorer
     let sections = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Min(2)])
         .split(main_block.inner(chunk));
-    let decklist_msg = if app.decklist.is_none() {
+    let decklist_msg = if app.decklist.is_none() && app.diff_base.is_some() {
+        format!(
+            "Please select the new version of {} to compare to.",
+            app.diff_base_name
+        )
+    } else if app.decklist.is_none() {
         format!("Please select a decklist. | {}", app.decklist_status)
+    } else if app.deck_input_mode == Some(DeckInputMode::Add) {
+        format!("Add card: {}_", app.deck_name_input.text)
+    } else if app.deck_input_mode == Some(DeckInputMode::Rename) {
+        format!("Rename to: {}_", app.deck_name_input.text)
+    } else if app.deck_input_mode == Some(DeckInputMode::SaveAs) {
+        format!(
+            "Save as: {}_ | {}",
+            app.deck_name_input.text, app.deck_edit_status
+        )
+    } else if app.deck_editing {
+        format!(
+            "Editing {}{} | {}",
+            app.decklist_file_name.as_deref().unwrap_or_default(),
+            if app.deck_unsaved { " (unsaved)" } else { "" },
+            app.deck_edit_status
+        )
     } else {
         format!(
-            "Decklist loaded successfully.  Using {}",
-            app.decklist_file_name.as_ref().unwrap() // NOTE: should exist if you get to this branch
+            "Decklist loaded successfully.  Using {}{}",
+            app.decklist_file_name.as_ref().unwrap(), // NOTE: should exist if you get to this branch
+            if app.deck_unsaved {
+                format!(
+                    " (unsaved changes, press {} then w to save)",
+                    key_name(app, Action::Edit)
+                )
+            } else {
+                String::new()
+            }
         )
     };
     let file_paragraph = Paragraph::new(decklist_msg).wrap(Wrap { trim: true });
@@ -386,18 +652,55 @@ fn draw_decklist_main(
         // further split area  for format legality info
         let subs = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Fill(1), Constraint::Min(3)])
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Percentage(35),
+                Constraint::Length(28),
+            ])
             .split(sections[1]);
+        let decklist = app.decklist.as_ref().unwrap();
+        let texts: Vec<String> = decklist
+            .iter()
+            .map(|card| match card.section {
+                DeckSection::Commander => format!("CMDR: {}", card),
+                DeckSection::Main => format!("{}", card),
+                DeckSection::Sideboard => format!("SB: {}", card),
+            })
+            .collect();
+        // commander decks show how popular each card is on EDHREC
+        let show_rank = !app.dc.database_cards.is_empty()
+            && decklist.iter().any(|c| c.section == DeckSection::Commander);
+        let spacing = texts.iter().map(|t| t.chars().count()).max().unwrap_or(0) + 2;
         let mut lines: Vec<Line> = Vec::new();
-        for card in app.decklist.as_ref().unwrap() {
-            lines.push(Line::from(format!("{}", card)));
+        for (card, text) in decklist.iter().zip(texts) {
+            let rank = app
+                .dc
+                .database_cards
+                .get(&make_safe_name(&card.name, true))
+                .map(|m| m.edhrec_rank)
+                .filter(|r| *r > 0 && show_rank);
+            let line = match rank {
+                Some(r) => {
+                    let padding = space_padding(spacing - text.chars().count());
+                    Line::from(vec![
+                        Span::from(text),
+                        Span::from(padding),
+                        Span::from(format!("#{}", r)).fg(p.info),
+                    ])
+                }
+                None => Line::from(text),
+            };
+            lines.push(line);
         }
-        app.decklist_scroll_state = app.collection_scroll_state.content_length(lines.len());
-        let decklist_paragraph = Paragraph::new(lines[app.decklist_scroll..].to_vec());
+        mark_matches(app, &mut lines);
+        app.decklist_scroll_state = app.decklist_scroll_state.content_length(lines.len());
+        app.decklist_list_state.select(Some(app.decklist_scroll));
+        let decklist_list = List::new(lines).highlight_style(Style::default().reversed());
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("^"))
             .end_symbol(Some("v"));
-        frame.render_widget(decklist_paragraph, subs[0]);
+        frame.render_stateful_widget(decklist_list, subs[0], &mut app.decklist_list_state);
+        app.list_area = subs[0];
         frame.render_stateful_widget(
             scrollbar,
             subs[0].inner(Margin {
@@ -406,117 +709,66 @@ fn draw_decklist_main(
             }),
             &mut app.decklist_scroll_state,
         );
+        if app.deck_input_mode.is_some() {
+            app.deck_name_input.draw_suggestions(frame, subs[1]);
+        } else if app.deck_diff.is_some() {
+            draw_deck_diff(app, frame, subs[1]);
+        } else if app.stats_view {
+            draw_deck_stats(app, frame, subs[1]);
+        } else {
+            let selected = app
+                .decklist
+                .as_ref()
+                .and_then(|cards| cards.get(app.decklist_scroll))
+                .map(|c| c.name.as_str());
+            draw_card_detail(frame, subs[1], selected, &app.dc.database_cards, p);
+        }
+        // commander decks get their own check above the format legality
+        let legality_area = match &app.commander_check {
+            Some(check) => {
+                let lines = commander_lines(check, p);
+                let width = subs[2].width.saturating_sub(2).max(1) as usize;
+                let height: usize = lines.iter().map(|l| l.width().div_ceil(width).max(1)).sum();
+                let areas = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(height as u16 + 2), Constraint::Min(0)])
+                    .split(subs[2]);
+                let block = Block::default()
+                    .title("| Commander |")
+                    .borders(Borders::ALL);
+                frame.render_widget(
+                    Paragraph::new(lines).wrap(Wrap { trim: true }).block(block),
+                    areas[0],
+                );
+                areas[1]
+            }
+            None => subs[2],
+        };
         // style text based on legality
         if app.legality.is_some() {
             let fl = app.legality.as_ref().unwrap();
-            // this is annoyingly redundant but spans have lifetimes, so making this a function is
-            // even more annoying ¯\_(ツ)_/¯
-            let standard_text = if fl.standard {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let future_text = if fl.future {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let historic_text = if fl.historic {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let timeless_text = if fl.timeless {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let gladiator_text = if fl.gladiator {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let pioneer_text = if fl.pioneer {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let modern_text = if fl.modern {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let legacy_text = if fl.legacy {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let pauper_text = if fl.pauper {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let vintage_text = if fl.vintage {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let penny_text = if fl.penny {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let commander_text = if fl.commander {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let oathbreaker_text = if fl.oathbreaker {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let standard_brawl_text = if fl.standardbrawl {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let brawl_text = if fl.brawl {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let alchemy_text = if fl.alchemy {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let pauper_commander_text = if fl.paupercommander {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let duel_text = if fl.duel {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let oldschool_text = if fl.oldschool {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let premodern_text = if fl.premodern {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let predh_text = if fl.predh {
-                Span::from("LEGAL").green()
-            } else {
-                Span::from("NOT LEGAL").red()
-            };
-            let legal_lines = Paragraph::new(vec![
+            let standard_text = p.legal_span(fl.standard);
+            let future_text = p.legal_span(fl.future);
+            let historic_text = p.legal_span(fl.historic);
+            let timeless_text = p.legal_span(fl.timeless);
+            let gladiator_text = p.legal_span(fl.gladiator);
+            let pioneer_text = p.legal_span(fl.pioneer);
+            let modern_text = p.legal_span(fl.modern);
+            let legacy_text = p.legal_span(fl.legacy);
+            let pauper_text = p.legal_span(fl.pauper);
+            let vintage_text = p.legal_span(fl.vintage);
+            let penny_text = p.legal_span(fl.penny);
+            let commander_text = p.legal_span(fl.commander);
+            let oathbreaker_text = p.legal_span(fl.oathbreaker);
+            let standard_brawl_text = p.legal_span(fl.standardbrawl);
+            let brawl_text = p.legal_span(fl.brawl);
+            let alchemy_text = p.legal_span(fl.alchemy);
+            let pauper_commander_text = p.legal_span(fl.paupercommander);
+            let duel_text = p.legal_span(fl.duel);
+            let oldschool_text = p.legal_span(fl.oldschool);
+            let premodern_text = p.legal_span(fl.premodern);
+            let predh_text = p.legal_span(fl.predh);
+            let mut lines = vec![
                 Line::from(vec![Span::from("Standard: ").bold(), standard_text]),
                 Line::from(vec![Span::from("Pioneer: ").bold(), pioneer_text]),
                 Line::from(vec![Span::from("Modern: ").bold(), modern_text]),
@@ -544,8 +796,15 @@ fn draw_decklist_main(
                 Line::from(vec![Span::from("Duel: ").bold(), duel_text]),
                 Line::from(vec![Span::from("Future: ").bold(), future_text]),
                 Line::from(vec![Span::from("Oathbreaker: ").bold(), oathbreaker_text]),
-            ]);
-            frame.render_widget(legal_lines, subs[1]);
+            ];
+            if !fl.not_in_game.is_empty() {
+                lines.push(Line::from(vec![
+                    Span::from(format!("Not on {}: ", app.collection_source.label())).bold(),
+                    Span::from(fl.not_in_game.join(", ")).fg(p.bad),
+                ]));
+            }
+            let legal_lines = Paragraph::new(lines).wrap(Wrap { trim: true });
+            frame.render_widget(legal_lines, legality_area);
         }
     } else {
         frame.render_widget(&explorer.widget(), sections[1]);
@@ -556,10 +815,323 @@ fn draw_decklist_main(
     app.decklist_file = Some(file.clone());
 }
 
+/// the commanders and every deck building rule they were checked against
+fn commander_lines(check: &CommanderCheck, p: Palette) -> Vec<Line<'static>> {
+    let mark = |ok: bool| {
+        if ok {
+            Span::from("✓ ").fg(p.good)
+        } else {
+            Span::from("✗ ").fg(p.bad).bold()
+        }
+    };
+    let rule = |ok: bool, passed: String, failed: &[String], problem: &str| {
+        let text = if ok {
+            passed
+        } else {
+            format!("{}: {}", problem, failed.join(", "))
+        };
+        Line::from(vec![mark(ok), Span::from(text)])
+    };
+    let mut lines: Vec<Line> = check
+        .commanders
+        .iter()
+        .map(|name| Line::from(Span::from(name.clone()).bold()))
+        .collect();
+    lines.push(Line::from(format!("Identity: {}", check.identity_str())));
+    if let Some(problem) = &check.pair_problem {
+        lines.push(Line::from(vec![mark(false), Span::from(problem.clone())]));
+    }
+    lines.push(Line::from(vec![
+        mark(check.card_count == DECK_SIZE),
+        Span::from(format!("{} of {} cards", check.card_count, DECK_SIZE)),
+    ]));
+    lines.push(rule(
+        check.duplicates.is_empty(),
+        "Singleton".to_string(),
+        &check.duplicates,
+        "Duplicates",
+    ));
+    lines.push(rule(
+        check.off_identity.is_empty(),
+        "Color identity".to_string(),
+        &check.off_identity,
+        "Off identity",
+    ));
+    lines.push(rule(
+        check.banned.is_empty(),
+        "Ban list".to_string(),
+        &check.banned,
+        "Banned",
+    ));
+    if !check.not_legal.is_empty() {
+        lines.push(Line::from(vec![
+            mark(false),
+            Span::from(format!("Not legal: {}", check.not_legal.join(", "))),
+        ]));
+    }
+    if !check.not_found.is_empty() {
+        lines.push(
+            Line::from(format!("Not in database: {}", check.not_found.join(", "))).fg(p.warn),
+        );
+    }
+    lines.push(Line::from(vec![
+        Span::from("Commander: ").bold(),
+        p.legal_span(check.legal()),
+    ]));
+    if let Some(average) = check.average_rank {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::from("Avg EDHREC rank: ").bold(),
+            Span::from(format!("{:.0}", average)).fg(p.info),
+        ]));
+        lines.push(Line::from(Span::from("Least played:").bold()));
+        for (name, rank) in check.least_played.iter() {
+            lines.push(Line::from(vec![
+                Span::from(format!("#{} ", rank)).fg(p.info),
+                Span::from(name.clone()),
+            ]));
+        }
+    }
+    lines
+}
+
+/// draws the mana curve, color pips and card type split of the loaded deck
+fn draw_deck_stats(app: &App, frame: &mut Frame, area: Rect) {
+    let p = app.config.palette.palette();
+    let block = Block::default().title("| Stats |").borders(Borders::ALL);
+    let stats = match &app.deck_stats {
+        Some(s) => s,
+        None => {
+            let msg = if app.dc.database_cards.is_empty() {
+                "Deck stats need the card database."
+            } else {
+                "Calculating deck stats..."
+            };
+            frame.render_widget(Paragraph::new(msg).block(block), area);
+            return;
+        }
+    };
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(6),
+            Constraint::Length(5),
+            Constraint::Length(7),
+            Constraint::Length(5),
+        ])
+        .split(block.inner(area));
+    let curve: Vec<(&str, u64)> = CURVE_BUCKETS
+        .iter()
+        .zip(stats.curve.iter())
+        .map(|(label, count)| (*label, *count))
+        .collect();
+    let bar_chart = BarChart::default()
+        .block(Block::default().title("Mana Curve"))
+        .data(&curve)
+        .bar_width(3)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(p.info))
+        .value_style(Style::default().black().on_cyan());
+    let mut pip_spans = vec![Span::from("Pips: ").bold()];
+    for (symbol, count) in PIP_SYMBOLS.iter().zip(stats.pips.iter()) {
+        if *count > 0 {
+            pip_spans.push(Span::from(format!("{}:{} ", symbol, count)));
+        }
+    }
+    let suggested = stats.suggested_lands();
+    let land_span = if stats.lands.abs_diff(suggested) <= 1 {
+        Span::from(format!("{} lands", stats.lands)).fg(p.good)
+    } else {
+        Span::from(format!("{} lands", stats.lands)).fg(p.warn)
+    };
+    let mut lines = vec![
+        Line::from(pip_spans),
+        Line::from(vec![
+            land_span,
+            Span::from(format!(
+                " / {} creatures / {} other spells",
+                stats.creatures, stats.spells
+            )),
+        ]),
+        Line::from(vec![
+            Span::from("Average mana value: ").bold(),
+            Span::from(format!("{:.2} (without lands)", stats.average_cmc)),
+        ]),
+        Line::from(vec![
+            Span::from("Suggested lands: ").bold(),
+            Span::from(format!(
+                "~{} for {} cards, you have {}",
+                suggested, stats.total, stats.lands
+            )),
+        ]),
+    ];
+    if stats.not_found > 0 {
+        lines.push(Line::from(
+            format!("{} cards were not found in the database.", stats.not_found).fg(p.bad),
+        ));
+    }
+    frame.render_widget(block, area);
+    frame.render_widget(bar_chart, sections[0]);
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), sections[1]);
+    frame.render_widget(Paragraph::new(odds_lines(app, stats.lands)), sections[2]);
+    let hand_lines = match &app.sample_hand {
+        Some(hand) => vec![
+            Line::from(Span::from(format!("Sample hand (seed {})", app.sample_seed)).bold()),
+            Line::from(hand.join(", ")),
+        ],
+        None => vec![Line::from(format!(
+            "Press {} to draw a sample opening hand.",
+            key_name(app, Action::SampleHand)
+        ))],
+    };
+    frame.render_widget(
+        Paragraph::new(hand_lines).wrap(Wrap { trim: true }),
+        sections[3],
+    );
+}
+
+/// draws the changes from the old version of the deck and the cards needed for the new one
+fn draw_deck_diff(app: &App, frame: &mut Frame, area: Rect) {
+    let p = app.config.palette.palette();
+    let block = Block::default()
+        .title(format!("| Changes from {} |", app.diff_base_name))
+        .borders(Borders::ALL);
+    let diff = match &app.deck_diff {
+        Some(d) => d,
+        None => return,
+    };
+    let mut lines = Vec::new();
+    if diff.changes.is_empty() {
+        lines.push(Line::from("The decklists are the same."));
+    }
+    let mut section = None;
+    for change in diff.changes.iter() {
+        if section != Some(change.section) {
+            lines.push(Line::from(Span::from(change.section.to_string()).bold()));
+            section = Some(change.section);
+        }
+        let text = format!(
+            "{} {} -> {} {}",
+            change.symbol(),
+            change.old,
+            change.new,
+            change.name
+        );
+        lines.push(match change.symbol() {
+            "+" => Line::from(text).fg(p.good),
+            "-" => Line::from(text).fg(p.bad),
+            _ => Line::from(text).fg(p.warn),
+        });
+    }
+    if !diff.needs.is_empty() {
+        let symbol = app.currency().symbol();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::from("Needed").bold()));
+        for need in diff.needs.iter() {
+            let price = match need.unit_price {
+                Some(price) => {
+                    Span::from(format!("{}{:.2}", symbol, price * need.to_buy as f64)).fg(p.price)
+                }
+                None => Span::from("not in database").fg(p.bad),
+            };
+            lines.push(Line::from(vec![
+                Span::from(format!(
+                    "{} {} (+{}, own {}) ",
+                    need.to_buy, need.name, need.added, need.owned
+                )),
+                price,
+            ]));
+        }
+        lines.push(Line::from(vec![
+            Span::from("Total: ").bold(),
+            Span::from(format!("{}{:.2}", symbol, diff.total_cost())).fg(p.price),
+        ]));
+    }
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// odds of drawing the highlighted card and enough lands on the first few turns
+fn odds_lines(app: &App, lands: u64) -> Vec<Line<'static>> {
+    let p = app.config.palette.palette();
+    const TURNS: u64 = 4;
+    let decklist = match &app.decklist {
+        Some(d) => main_deck(d),
+        None => return Vec::new(),
+    };
+    let deck_size: u64 = decklist.iter().map(|c| c.quantity).sum();
+    let mut lines = Vec::new();
+    let mut odds_row = |label: &str, successes: u64, at_least: &dyn Fn(u64) -> u64| {
+        for (name, on_play) in [("play", true), ("draw", false)] {
+            let mut spans = vec![Span::from(format!("{} on the {}: ", label, name))];
+            for turn in 1..=TURNS {
+                let odds = prob_by_turn(deck_size, successes, at_least(turn), turn, on_play);
+                spans.push(Span::from(format!("T{} {:.0}% ", turn, odds * 100.0)).fg(p.price));
+            }
+            lines.push(Line::from(spans));
+        }
+    };
+    // sideboard cards are counted by their copies in the main deck, if there are any
+    if let Some(card) = app
+        .decklist
+        .as_ref()
+        .and_then(|d| d.get(app.decklist_scroll))
+    {
+        let copies = decklist
+            .iter()
+            .filter(|c| c.name == card.name)
+            .map(|c| c.quantity)
+            .sum();
+        if copies > 0 {
+            odds_row(&format!("1+ {}", card.name), copies, &|_| 1);
+        }
+    }
+    // a land drop every turn
+    odds_row("Land drop every turn", lands, &|turn| turn);
+    let mut odds_lines = vec![Line::from(Span::from("Draw Odds").bold())];
+    odds_lines.extend(lines);
+    odds_lines
+}
+
 /// draws the main block of the missing cards tab
 fn draw_missing_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let p = app.config.palette.palette();
     if app.missing_cards.is_some() {
-        let inner_area = main_block.inner(chunk);
+        let subs = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Fill(1), Constraint::Percentage(40)])
+            .split(main_block.inner(chunk));
+        let unit_prices = unit_prices(
+            app.missing_cards.as_deref().unwrap_or_default(),
+            &app.dc.database_cards,
+            &app.currency(),
+        );
+        let plan = match (&app.missing_cards, &app.decklist) {
+            (Some(missing), Some(decklist))
+                if app.config.deck_budget > 0.0 && app.price_done && !app.wildcard_mode =>
+            {
+                Some(budget_plan(
+                    missing,
+                    decklist,
+                    &unit_prices,
+                    app.config.deck_budget,
+                ))
+            }
+            _ => None,
+        };
+        let list_area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(if plan.is_some() || app.wildcard_mode {
+                    3
+                } else {
+                    2
+                }),
+            ])
+            .split(subs[0]);
         /* moving for speed
         let mut lines: Vec<Line> = Vec::new();
         for card in app.missing_cards.clone().unwrap() {
@@ -584,8 +1156,25 @@ fn draw_missing_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block:
             }
         }
         spacing += 5;
+        // line totals in every currency, for the side by side columns
+        let columns: Vec<[Option<f64>; 3]> = match (&app.missing_cards, app.config.price_columns) {
+            (Some(missing), true) => missing
+                .iter()
+                .map(|card| {
+                    let matched = app.dc.database_cards.get(&make_safe_name(&card.name, true));
+                    CURRENCIES.map(|currency| {
+                        matched
+                            .and_then(|m| m.lowest_price(&currency))
+                            .map(|p| p * card.quantity as f64)
+                    })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
         for (i, line_str) in app.missing_lines.iter().enumerate() {
-            let price_str = if app.price_done
+            let price_str = if let Some(column) = columns.get(i) {
+                currency_columns(column)
+            } else if app.price_done
                 && app.missing_price.is_some()
                 && app.missing_price.as_ref().unwrap().len() > i
             {
@@ -593,43 +1182,130 @@ fn draw_missing_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block:
             } else {
                 "".to_string()
             };
-            missing_lines.push(Line::from(vec![
+            let unit_price = unit_prices.get(i).copied().flatten();
+            if app.wildcard_mode {
+                let matched = app
+                    .missing_cards
+                    .as_ref()
+                    .and_then(|m| m.get(i))
+                    .and_then(|c| app.dc.database_cards.get(&make_safe_name(&c.name, true)));
+                let cost = WildcardCost::of(matched);
+                let mut line = Line::from(vec![
+                    Span::from(line_str.clone()),
+                    Span::from(space_padding(spacing - line_str.len())),
+                    match cost {
+                        WildcardCost::NotOnArena | WildcardCost::NotFound => {
+                            Span::from(cost.label()).fg(p.bad)
+                        }
+                        WildcardCost::UnknownRarity => Span::from(cost.label()).fg(p.warn),
+                        _ => Span::from(cost.label()).fg(p.info),
+                    },
+                ]);
+                if matched.is_some_and(is_alchemy) {
+                    line.push_span(Span::from(" Alchemy").fg(p.warn).bold());
+                }
+                missing_lines.push(line);
+                continue;
+            }
+            let price_span = if over_threshold(unit_price, app.config.price_threshold) {
+                Span::from(format!("{} ▲", price_str)).fg(p.bad).bold()
+            } else {
+                Span::from(price_str).fg(p.price)
+            };
+            let mut line = Line::from(vec![
                 Span::from(line_str.clone()),
                 Span::from(space_padding(spacing - line_str.len())),
-                Span::from(price_str).magenta(),
-            ]));
+                price_span,
+            ]);
+            if let Some(plan) = &plan {
+                let quantity = app
+                    .missing_cards
+                    .as_ref()
+                    .and_then(|m| m.get(i))
+                    .map(|c| c.quantity)
+                    .unwrap_or(0);
+                match plan.buy.get(i).copied().unwrap_or(0) {
+                    0 => line = line.dim(),
+                    buy if buy < quantity => {
+                        line.push_span(Span::from(format!(" (buy {})", buy)).fg(p.warn))
+                    }
+                    _ => {}
+                }
+            }
+            missing_lines.push(line);
         }
         // add final total
-        missing_lines.push(Line::from("\n"));
-        let currency_str = match app.config.currency {
+        let currency_str = match app.currency() {
             PriceType::USD => "$".to_string(),
             PriceType::Euro => "€".to_string(),
             PriceType::Tix => "Tix ".to_string(),
         };
-        missing_lines.push(Line::from(vec![
-            Span::from("Total: ").light_red().bold().underlined(),
-            Span::from(space_padding(spacing - 7)),
-            Span::from(currency_str).light_red().bold().underlined(),
-            Span::from(format!(
-                "{:.2}",
-                app.missing_price_num
-                    .as_ref()
-                    .unwrap_or(&vec![0.0]) // TODO: this unwrap is lazy
-                    .iter()
-                    .sum::<f64>()
-            ))
-            .light_red()
-            .bold()
-            .underlined(),
-        ]));
-        let missing_paragraph = Paragraph::new(missing_lines[app.missing_scroll..].to_vec());
-        //.scroll((app.missing_scroll as u16, 0))
+        let missing_total = app
+            .missing_price_num
+            .as_ref()
+            .unwrap_or(&vec![0.0]) // TODO: this unwrap is lazy
+            .iter()
+            .sum::<f64>();
+        let total_line = if columns.is_empty() {
+            Line::from(vec![
+                Span::from("Total: ").fg(p.total).bold().underlined(),
+                Span::from(space_padding(spacing - 7)),
+                Span::from(currency_str).fg(p.total).bold().underlined(),
+                Span::from(format!("{:.2}", missing_total))
+                    .fg(p.total)
+                    .bold()
+                    .underlined(),
+            ])
+        } else {
+            let totals = [0, 1, 2].map(|c| Some(columns.iter().filter_map(|col| col[c]).sum()));
+            Line::from(vec![
+                Span::from("Total: ").fg(p.total).bold().underlined(),
+                Span::from(space_padding(spacing - 7)),
+                Span::from(currency_columns(&totals))
+                    .fg(p.total)
+                    .bold()
+                    .underlined(),
+            ])
+        };
+        mark_matches(app, &mut missing_lines);
+        app.missing_list_state.select(Some(app.missing_scroll));
+        let missing_list = List::new(missing_lines).highlight_style(Style::default().reversed());
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("^"))
             .end_symbol(Some("v"));
         main_block.render(chunk, frame.buffer_mut());
-        frame.render_widget(missing_paragraph, inner_area);
-        frame.render_stateful_widget(scrollbar, inner_area, &mut app.missing_scroll_state);
+        frame.render_stateful_widget(missing_list, list_area[0], &mut app.missing_list_state);
+        app.list_area = list_area[0];
+        let mut summary = if app.wildcard_mode {
+            wildcard_lines(app)
+        } else {
+            vec![Line::from(""), total_line]
+        };
+        if let Some(plan) = &plan {
+            summary.push(budget_line(app, plan, missing_total));
+        }
+        frame.render_widget(Paragraph::new(summary), list_area[1]);
+        frame.render_stateful_widget(scrollbar, list_area[0], &mut app.missing_scroll_state);
+        let selected = app
+            .missing_cards
+            .as_ref()
+            .and_then(|cards| cards.get(app.missing_scroll))
+            .map(|c| c.name.as_str());
+        let detail_area = match &app.substitutes {
+            Some((name, _, _)) if Some(name.as_str()) == selected => {
+                let areas = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Min(6),
+                        Constraint::Length(MAX_SUBSTITUTES as u16 + 3),
+                    ])
+                    .split(subs[1]);
+                draw_substitutes(app, frame, areas[1]);
+                areas[0]
+            }
+            _ => subs[1],
+        };
+        draw_card_detail(frame, detail_area, selected, &app.dc.database_cards, p);
     } else {
         if app.decklist.is_some() && !app.waiting_for_missing {
             let missing_paragraph = Paragraph::new("No missing cards!").block(main_block);
@@ -645,16 +1321,601 @@ fn draw_missing_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block:
     }
 }
 
+/// currencies in the side by side price columns
+const CURRENCIES: [PriceType; 3] = [PriceType::USD, PriceType::Euro, PriceType::Tix];
+
+/// prices in USD, EUR and Tix lined up in columns
+fn currency_columns(prices: &[Option<f64>; 3]) -> String {
+    CURRENCIES
+        .iter()
+        .zip(prices.iter())
+        .map(|(currency, price)| match price {
+            Some(p) => format!("{:>12}", format!("{}{:.2}", currency.symbol(), p)),
+            None => format!("{:>12}", "-"),
+        })
+        .collect()
+}
+
+/// wildcards needed for the missing cards, and the copies that can't be crafted
+fn wildcard_lines(app: &App) -> Vec<Line<'static>> {
+    let p = app.config.palette.palette();
+    let total = wildcard_total(
+        app.missing_cards.as_deref().unwrap_or_default(),
+        &app.dc.database_cards,
+    );
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::from("Wildcards: ").fg(p.total).bold().underlined(),
+            Span::from(format!(
+                "{} mythic, {} rare, {} uncommon, {} common ({} total)",
+                total.mythic,
+                total.rare,
+                total.uncommon,
+                total.common,
+                total.total()
+            ))
+            .fg(p.total)
+            .bold(),
+        ]),
+    ];
+    let mut notes = Vec::new();
+    if total.not_on_arena > 0 {
+        notes.push(Span::from(format!("{} copies not on Arena ", total.not_on_arena)).fg(p.bad));
+    }
+    if total.unknown_rarity > 0 {
+        notes.push(
+            Span::from(format!(
+                "{} copies with no known Arena printing, not counted ",
+                total.unknown_rarity
+            ))
+            .fg(p.warn),
+        );
+    }
+    if total.alchemy > 0 {
+        notes.push(Span::from(format!("{} Alchemy copies", total.alchemy)).fg(p.warn));
+    }
+    if !notes.is_empty() {
+        lines.push(Line::from(notes));
+    }
+    lines
+}
+
+/// how the missing cards compare to the deck budget, and what fits in it
+fn budget_line(app: &App, plan: &BudgetPlan, total: f64) -> Line<'static> {
+    let p = app.config.palette.palette();
+    let symbol = app.currency().symbol();
+    let copies: u64 = app
+        .missing_cards
+        .as_ref()
+        .map(|m| m.iter().map(|c| c.quantity).sum())
+        .unwrap_or(0);
+    let status = if total > plan.budget {
+        Span::from(format!("{}{:.2} over", symbol, total - plan.budget)).fg(p.bad)
+    } else {
+        Span::from(format!("{}{:.2} under", symbol, plan.budget - total)).fg(p.good)
+    };
+    let mut spans = vec![
+        Span::from(format!("Budget {}{:.2}: ", symbol, plan.budget)).bold(),
+        status,
+        Span::from(format!(
+            ", {} of {} copies fit for {}{:.2}",
+            plan.copies(),
+            copies,
+            symbol,
+            plan.cost
+        )),
+    ];
+    if plan.skipped > 0 {
+        spans.push(Span::from(format!(", {} not in the database", plan.skipped)).fg(p.warn));
+    }
+    Line::from(spans)
+}
+
+/// cheaper cards that could replace the highlighted missing card
+fn draw_substitutes(app: &App, frame: &mut Frame, area: Rect) {
+    let p = app.config.palette.palette();
+    let (format, substitutes) = match &app.substitutes {
+        Some((_, format, substitutes)) => (format, substitutes),
+        None => return,
+    };
+    let title = match format {
+        Some(f) => format!("| Cheaper Substitutes, {} legal |", format_label(f)),
+        None => "| Cheaper Substitutes |".to_string(),
+    };
+    let symbol = app.currency().symbol();
+    let mut lines: Vec<Line> = substitutes
+        .iter()
+        .map(|s| {
+            let mut spans = vec![
+                Span::from(format!("{} ", s.name)),
+                Span::from(format!("{}{:.2}", symbol, s.unit_price)).fg(p.price),
+            ];
+            if s.owned > 0 {
+                spans.push(Span::from(format!(" {} owned", s.owned)).fg(p.good));
+            }
+            spans.push(Span::from(format!(" saves {}{:.2}", symbol, s.savings)).fg(p.total));
+            Line::from(spans)
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from("No cheaper substitutes found."));
+    }
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL)),
+        area,
+    );
+}
+
 /// draws the main block of the help tab
-fn draw_help_main(frame: &mut Frame, chunk: Rect, main_block: Block) {
+fn draw_help_main(app: &App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let p = app.config.palette.palette();
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(2), Constraint::Length(50)])
+        .split(main_block.inner(chunk));
     let subs = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(2), Constraint::Min(2), Constraint::Min(2)])
-        .split(main_block.inner(chunk));
+        .split(columns[0]);
+    frame.render_widget(
+        Paragraph::new(key_binding_lines(app)).block(
+            Block::default()
+                .title("| Key Bindings |")
+                .borders(Borders::ALL),
+        ),
+        columns[1],
+    );
     let help_paragraph = Paragraph::new(HELP_STR).wrap(Wrap { trim: true });
-    let about_paragraph = Paragraph::new(ABOUT_STR).wrap(Wrap { trim: true }).cyan();
-    let bug_paragraph = Paragraph::new(BUG_STR).wrap(Wrap { trim: true }).magenta();
+    let about_paragraph = Paragraph::new(ABOUT_STR)
+        .wrap(Wrap { trim: true })
+        .fg(p.info);
+    let bug_paragraph = Paragraph::new(BUG_STR)
+        .wrap(Wrap { trim: true })
+        .fg(p.price);
     frame.render_widget(help_paragraph, subs[0]);
     frame.render_widget(about_paragraph, subs[1]);
     frame.render_widget(bug_paragraph, subs[2]);
 }
+
+/// the keys bound to every action, from the [keybindings] section of config.toml
+fn key_binding_lines(app: &App) -> Vec<Line<'static>> {
+    let p = app.config.palette.palette();
+    let mut lines: Vec<Line> = Action::ALL
+        .iter()
+        .map(|action| {
+            let keys = app.bound_keys(*action).join(", ");
+            Line::from(vec![
+                Span::from(format!("{:<32}", action.label())),
+                Span::from(keys).fg(p.warn).bold(),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(
+        "Fixed keys: + - m a r w e Esc in the deck editor, and typing in text inputs and file browsers",
+    ));
+    for error in app.key_errors.iter() {
+        lines.push(Line::from(error.clone()).fg(p.bad));
+    }
+    lines
+}
+
+/// draws the main block of the collection value tab
+fn draw_value_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let p = app.config.palette.palette();
+    let value = match &app.collection_value {
+        Some(v) => v,
+        None => {
+            let msg = if app.collection.is_none() {
+                "Load a collection first."
+            } else if app.dc.database_cards.is_empty() {
+                "Collection value needs the card database."
+            } else {
+                "Calculating collection value..."
+            };
+            frame.render_widget(Paragraph::new(msg).block(main_block), chunk);
+            return;
+        }
+    };
+    let symbol = value.currency.symbol();
+    let history_height = if value.history.len() > 1 { 4 } else { 0 };
+    let summary_height = if value.cheapest_count() > 0 { 4 } else { 3 };
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(summary_height),
+            Constraint::Min(2),
+            Constraint::Length(history_height),
+        ])
+        .split(main_block.inner(chunk));
+    let mut summary = vec![Line::from(vec![
+        Span::from("Total: ").fg(p.total).bold().underlined(),
+        Span::from(format!("{}{:.2}", symbol, value.total))
+            .fg(p.total)
+            .bold()
+            .underlined(),
+    ])];
+    if !value.not_found.is_empty() {
+        summary.push(Line::from(format!(
+            "{} line items were not found in the database and are not counted.",
+            value.not_found.len()
+        )));
+    }
+    if value.cheapest_count() > 0 {
+        summary.push(Line::from(format!(
+            "{} line items are valued at the cheapest printing, their own isn't in the database.",
+            value.cheapest_count()
+        )));
+    }
+    summary.push(Line::from(app.value_status.clone()));
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(25),
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+            Constraint::Percentage(35),
+        ])
+        .split(sections[1]);
+    let groups = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(value.by_rarity.len() as u16 + 2),
+            Constraint::Min(2),
+        ])
+        .split(columns[1]);
+    let set_lines = breakdown_lines(
+        "By Set",
+        value.by_set.get(app.value_scroll..).unwrap_or(&[]),
+        symbol,
+        p,
+    );
+    let rarity_lines = breakdown_lines("By Rarity", &value.by_rarity, symbol, p);
+    let color_lines = breakdown_lines("By Color", &value.by_color, symbol, p);
+    let format_lines = breakdown_lines("By Format", &value.by_format, symbol, p);
+    frame.render_widget(main_block, chunk);
+    frame.render_widget(Paragraph::new(summary), sections[0]);
+    frame.render_widget(Paragraph::new(set_lines), columns[0]);
+    frame.render_widget(Paragraph::new(rarity_lines), groups[0]);
+    frame.render_widget(Paragraph::new(color_lines), groups[1]);
+    frame.render_widget(Paragraph::new(format_lines), columns[2]);
+    frame.render_widget(Paragraph::new(top_card_lines(value, p)), columns[3]);
+    if value.history.len() > 1 {
+        let first = value.history[0];
+        let last = value.history[value.history.len() - 1];
+        let data: Vec<u64> = value
+            .history
+            .iter()
+            .map(|(_, v)| (v * 100.0) as u64)
+            .collect();
+        let sparkline = Sparkline::default()
+            .block(Block::default().title(format!(
+                "Value history: {} {}{:.2} -> {} {}{:.2}",
+                first.0, symbol, first.1, last.0, symbol, last.1
+            )))
+            .data(&data)
+            .fg(p.price);
+        frame.render_widget(sparkline, sections[2]);
+    }
+}
+
+/// draws the trade list on the collection value tab
+fn draw_trade_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let p = app.config.palette.palette();
+    let trade_cards = match &app.trade_cards {
+        Some(t) => t,
+        None => {
+            let msg = if app.collection.is_none() {
+                "Load a collection first."
+            } else if app.dc.database_cards.is_empty() {
+                "The trade list needs the card database."
+            } else {
+                "Looking for surplus cards..."
+            };
+            frame.render_widget(Paragraph::new(msg).block(main_block), chunk);
+            return;
+        }
+    };
+    let symbol = app.currency().symbol();
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(2)])
+        .split(main_block.inner(chunk));
+    let keep = if app.config.trade_singleton {
+        "1 copy"
+    } else {
+        "a playset"
+    };
+    let summary = vec![
+        Line::from(vec![
+            Span::from("Trade list: ").fg(p.total).bold().underlined(),
+            Span::from(format!(
+                "{} cards worth {}{:.2}",
+                trade_cards.iter().map(|c| c.quantity).sum::<u64>(),
+                symbol,
+                trade_cards.iter().map(|c| c.total()).sum::<f64>()
+            ))
+            .fg(p.total)
+            .bold()
+            .underlined(),
+        ]),
+        Line::from(format!(
+            "Keeping {} plus copies used by {} saved decks, minimum value {}{:.2}, exporting as {:?}",
+            keep,
+            app.config.saved_decks.len(),
+            symbol,
+            app.config.trade_min_value,
+            app.config.trade_format
+        )),
+        Line::from(app.value_status.clone()),
+    ];
+    let mut lines = Vec::new();
+    for card in trade_cards.iter().skip(app.trade_scroll) {
+        lines.push(Line::from(vec![
+            Span::from(format!(
+                "{} {} ({}) ",
+                card.quantity,
+                card.name,
+                card.edition.to_uppercase()
+            )),
+            Span::from(format!(
+                "[{:.2}] = {}{:.2}",
+                card.unit_price,
+                symbol,
+                card.total()
+            ))
+            .fg(p.price),
+        ]));
+    }
+    frame.render_widget(main_block, chunk);
+    frame.render_widget(Paragraph::new(summary), sections[0]);
+    frame.render_widget(Paragraph::new(lines), sections[1]);
+}
+
+/// draws the set completion tab, with the sets on the left and the missing printings of the
+/// selected set on the right
+fn draw_sets_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let p = app.config.palette.palette();
+    let completion = match &app.set_completion {
+        Some(c) => c,
+        None => {
+            let msg = if app.collection.is_none() {
+                "Load a collection first."
+            } else if app.dc.sets.is_empty() {
+                "Set completion needs the set index, which is built when a Scryfall file is loaded."
+            } else {
+                "Checking set completion..."
+            };
+            frame.render_widget(Paragraph::new(msg).block(main_block), chunk);
+            return;
+        }
+    };
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(main_block.inner(chunk));
+    let mut set_lines = vec![Line::from(
+        Span::from(format!("Sets ({:?}), printings owned", app.rarity_filter)).bold(),
+    )];
+    if completion.is_empty() {
+        set_lines.push(Line::from(
+            "No cards in the collection matched a set.  The collection needs Edition and Collector Number columns.",
+        ));
+    }
+    // keep the selected set on screen
+    let height = columns[0].height.saturating_sub(1) as usize;
+    let start = (app.sets_selected + 1).saturating_sub(height);
+    for (i, set) in completion.iter().enumerate().skip(start) {
+        let line = Line::from(vec![
+            Span::from(format!("{} ({}) ", set.name, set.code.to_uppercase())),
+            Span::from(format!(
+                "{}/{} printings {:.1}%",
+                set.owned,
+                set.total,
+                set.percent()
+            ))
+            .fg(p.price),
+        ]);
+        if i == app.sets_selected {
+            set_lines.push(line.reversed());
+        } else {
+            set_lines.push(line);
+        }
+    }
+    let mut missing_lines = Vec::new();
+    if let Some(set) = completion.get(app.sets_selected) {
+        let currency = &app.currency();
+        let cost: f64 = set
+            .missing
+            .iter()
+            .filter_map(|p| p.prices.get(currency))
+            .sum();
+        missing_lines.push(Line::from(vec![
+            Span::from(format!("Missing from {}: ", set.name)).bold(),
+            Span::from(format!("{}{:.2}", currency.symbol(), cost))
+                .fg(p.total)
+                .bold(),
+        ]));
+        for printing in set.missing.iter().skip(app.sets_scroll) {
+            let price = match printing.prices.get(currency) {
+                Some(p) => format!("{}{:.2}", currency.symbol(), p),
+                None => "no price".to_string(),
+            };
+            missing_lines.push(Line::from(vec![
+                Span::from(format!(
+                    "#{} {} ({}) ",
+                    printing.collector_number, printing.name, printing.rarity
+                )),
+                Span::from(price).fg(p.price),
+            ]));
+        }
+    }
+    frame.render_widget(main_block, chunk);
+    frame.render_widget(Paragraph::new(set_lines), columns[0]);
+    frame.render_widget(Paragraph::new(missing_lines), columns[1]);
+}
+
+/// draws the search box and the cards that matched the last search
+fn draw_search_main(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let p = app.config.palette.palette();
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Min(2),
+        ])
+        .split(main_block.inner(chunk));
+    let input_block = Block::default().title("| Search |").borders(Borders::ALL);
+    let input_block = if app.search_editing {
+        input_block.fg(p.warn)
+    } else {
+        input_block
+    };
+    let input = Paragraph::new(app.search_input.text.clone()).block(input_block);
+    let symbol = app.config.currency.symbol();
+    let mut lines = Vec::new();
+    if let Some(results) = &app.search_results {
+        for result in results.iter().skip(app.search_scroll) {
+            let owned = if result.owned > 0 {
+                Span::from(format!("{:>3} owned ", result.owned)).fg(p.good)
+            } else {
+                Span::from("          ")
+            };
+            let price = match result.price {
+                Some(p) => format!("{}{:.2}", symbol, p),
+                None => "-".to_string(),
+            };
+            lines.push(Line::from(vec![
+                owned,
+                Span::from(format!("{} ", result.name)).bold(),
+                Span::from(format!("{} ", result.mana_cost)).fg(p.info),
+                Span::from(format!(
+                    "| {} | {} {} | ",
+                    result.type_line, result.set, result.rarity
+                )),
+                Span::from(price).fg(p.price),
+            ]));
+        }
+    } else {
+        lines.push(Line::from(format!(
+            "Press {} and type a Scryfall style search, e.g. {}",
+            key_name(app, Action::Select),
+            "t:creature c:g cmc<=2 f:modern usd<1 is:owned"
+        )));
+    }
+    frame.render_widget(main_block, chunk);
+    frame.render_widget(input, sections[0]);
+    frame.render_widget(Paragraph::new(app.search_status.clone()), sections[1]);
+    frame.render_widget(Paragraph::new(lines), sections[2]);
+    if app.search_editing && !app.search_input.suggestions.is_empty() {
+        let area = app.search_input.popup_area(sections[0], chunk, true);
+        app.search_input.draw_suggestions(frame, area);
+    }
+}
+
+/// highlights the rows matching the / search on the current list
+fn mark_matches(app: &App, lines: &mut [Line]) {
+    let p = app.config.palette.palette();
+    if app.list_query_tab != app.active_tab {
+        return;
+    }
+    for i in app.list_matches.iter() {
+        if let Some(line) = lines.get_mut(*i) {
+            *line = line.clone().fg(p.warn).bold();
+        }
+    }
+}
+
+/// the first key of each action for the instruction bar, like <Up/Down>
+fn key_hint(app: &App, actions: &[Action]) -> Span<'static> {
+    let p = app.config.palette.palette();
+    let keys: Vec<String> = actions
+        .iter()
+        .filter_map(|a| app.bound_keys(*a).into_iter().next())
+        .collect();
+    let text = if keys.is_empty() {
+        "<unbound>".to_string()
+    } else {
+        format!("<{}>", keys.join("/"))
+    };
+    Span::from(text).fg(p.key).bold()
+}
+
+/// the keys of the first and last numbered tab, like <1-9>
+fn tab_hint(app: &App) -> Span<'static> {
+    let p = app.config.palette.palette();
+    let first = app.bound_keys(Action::TabWelcome).into_iter().next();
+    let last = app.bound_keys(Action::TabSearch).into_iter().next();
+    let text = match (first, last) {
+        (Some(first), Some(last)) => format!("<{}-{}>", first, last),
+        _ => "<unbound>".to_string(),
+    };
+    Span::from(text).fg(p.key).bold()
+}
+
+/// the first key bound to an action, for messages that say what to press
+fn key_name(app: &App, action: Action) -> String {
+    app.bound_keys(action)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| format!("the {} key", action.name()))
+}
+
+/// replaces the instructions while searching a list with /
+fn list_query_text(app: &App) -> Text<'static> {
+    let p = app.config.palette.palette();
+    let mut spans = vec![
+        Span::from(format!("/{}", app.list_query_input.text))
+            .fg(p.warn)
+            .bold(),
+        Span::from(format!("  {}  ", app.list_query_status)),
+    ];
+    if app.list_query_editing {
+        spans.push("<Enter>".fg(p.key).bold());
+        spans.push(" Done ".into());
+    } else {
+        spans.push(key_hint(app, &[Action::NextMatch, Action::PreviousMatch]));
+        spans.push(" Next/Previous match ".into());
+    }
+    if app.list_query_editing {
+        spans.push("<Esc>".fg(p.key).bold());
+    } else {
+        spans.push(key_hint(app, &[Action::Back]));
+    }
+    spans.push(" Clear search ".into());
+    Text::from(Line::from(spans))
+}
+
+/// lines for one of the collection value breakdowns, with a title
+fn breakdown_lines<'a>(
+    title: &'a str,
+    breakdown: &[(String, f64)],
+    symbol: &str,
+    p: Palette,
+) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::from(Span::from(title).bold())];
+    for (name, amount) in breakdown {
+        lines.push(Line::from(vec![
+            Span::from(format!("{}: ", name)),
+            Span::from(format!("{}{:.2}", symbol, amount)).fg(p.price),
+        ]));
+    }
+    lines
+}
+
+/// lines for the most valuable cards in the collection
+fn top_card_lines(value: &CollectionValue, p: Palette) -> Vec<Line<'_>> {
+    let symbol = value.currency.symbol();
+    let mut lines = vec![Line::from(
+        Span::from(format!("Top {} Cards", DEFAULT_TOP_NUM)).bold(),
+    )];
+    for card in value.top(DEFAULT_TOP_NUM) {
+        lines.push(Line::from(vec![
+            Span::from(format!("{} {} ({}) ", card.quantity, card.name, card.set)),
+            Span::from(format!("{}{:.2}", symbol, card.total)).fg(p.price),
+        ]));
+    }
+    lines
+}
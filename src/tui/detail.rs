@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use ratatui::{
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    database::scryfall::{match_card, Legality, ScryfallCard, ScryfallPrices, FORMAT_NAMES},
+    tui::theme::Palette,
+};
+
+/// draws the Scryfall data for the highlighted card next to the Deck, Collection and Missing lists
+pub fn draw_card_detail(
+    frame: &mut Frame,
+    area: Rect,
+    name: Option<&str>,
+    database: &HashMap<String, ScryfallCard>,
+    p: Palette,
+) {
+    let block = Block::default().title("| Card |").borders(Borders::ALL);
+    let lines = match name {
+        None => vec![Line::from("Select a card to see its details.")],
+        Some(_) if database.is_empty() => {
+            vec![Line::from("Card details need the card database.")]
+        }
+        Some(n) => match match_card(n, database) {
+            Some(card) => card_detail_lines(&card, p),
+            None => vec![Line::from(format!("{} was not found in the database.", n)).fg(p.bad)],
+        },
+    };
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// every line of the card detail pane
+fn card_detail_lines(card: &ScryfallCard, p: Palette) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(vec![
+            Span::from(format!("{} ", card.name)).bold(),
+            Span::from(card.mana_cost.clone()).fg(p.info),
+        ]),
+        Line::from(card.type_line.clone().unwrap_or_default()).italic(),
+        Line::from(""),
+    ];
+    if card.oracle_text.is_empty() && !card.card_faces.is_empty() {
+        // double faced cards keep their text on each face
+        for face in card.card_faces.iter() {
+            lines.push(Line::from(vec![
+                Span::from(format!("{} ", face.name)).bold(),
+                Span::from(face.mana_cost.clone()).fg(p.info),
+            ]));
+            lines.push(Line::from(face.type_line.clone().unwrap_or_default()).italic());
+            for text_line in face.oracle_text.lines() {
+                lines.push(Line::from(text_line.to_string()));
+            }
+        }
+    } else {
+        for text_line in card.oracle_text.lines() {
+            lines.push(Line::from(text_line.to_string()));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::from("Rarity: ").bold(),
+        Span::from(card.rarity.to_string()),
+    ]));
+    lines.push(Line::from(vec![
+        Span::from("Set: ").bold(),
+        Span::from(format!(
+            "{} ({}) #{}",
+            card.set_name,
+            card.set.to_uppercase(),
+            card.collector_number
+        )),
+    ]));
+    let rank = if card.edhrec_rank > 0 {
+        card.edhrec_rank.to_string()
+    } else {
+        "-".to_string()
+    };
+    lines.push(Line::from(vec![
+        Span::from("EDHREC Rank: ").bold(),
+        Span::from(rank),
+    ]));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::from("Prices").bold()));
+    lines.extend(price_lines(&card.prices, p));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::from("Legality").bold()));
+    let groups = [
+        ("Legal: ", Legality::Legal),
+        ("Restricted: ", Legality::Restricted),
+        ("Banned: ", Legality::Banned),
+    ];
+    for (label, legality) in groups {
+        let formats: Vec<&str> = FORMAT_NAMES
+            .into_iter()
+            .filter(|f| card.legalities.get(f) == Some(&legality))
+            .collect();
+        if formats.is_empty() {
+            continue;
+        }
+        let span = Span::from(formats.join(", "));
+        let span = match legality {
+            Legality::Legal => span.fg(p.good),
+            Legality::Restricted => span.fg(p.warn),
+            _ => span.fg(p.bad),
+        };
+        lines.push(Line::from(vec![Span::from(label).bold(), span]));
+    }
+    lines
+}
+
+/// every price Scryfall has for the card, skipping the ones that are missing
+fn price_lines(prices: &ScryfallPrices, p: Palette) -> Vec<Line<'static>> {
+    let fields = [
+        ("USD", "$", &prices.usd),
+        ("USD Foil", "$", &prices.usd_foil),
+        ("USD Etched", "$", &prices.usd_etched),
+        ("EUR", "€", &prices.eur),
+        ("EUR Foil", "€", &prices.eur_foil),
+        ("Tix", "", &prices.tix),
+    ];
+    let mut lines = Vec::new();
+    for (label, symbol, price) in fields {
+        if let Some(price) = price {
+            lines.push(Line::from(vec![
+                Span::from(format!("{}: ", label)),
+                Span::from(format!("{}{}", symbol, price)).fg(p.price),
+            ]));
+        }
+    }
+    if lines.is_empty() {
+        lines.push(Line::from("No prices available."));
+    }
+    lines
+}
@@ -1,5 +1,8 @@
+pub mod autocomplete;
 pub mod core;
+pub mod detail;
 pub mod help;
+pub mod theme;
 
 /// generates a string with the requested number of spaces
 /// useful for padding text
@@ -0,0 +1,82 @@
+use ratatui::{
+    style::{Color, Stylize},
+    text::Span,
+};
+use serde::{Deserialize, Serialize};
+
+/// color palettes that can be picked in config.toml
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum PaletteName {
+    #[default]
+    Default,
+    HighContrast,
+    ColorblindSafe,
+    Monochrome,
+}
+
+impl PaletteName {
+    pub fn palette(&self) -> Palette {
+        match self {
+            PaletteName::Default => Palette {
+                good: Color::Green,
+                bad: Color::Red,
+                warn: Color::Yellow,
+                price: Color::Magenta,
+                total: Color::LightRed,
+                info: Color::Cyan,
+                key: Color::Yellow,
+            },
+            PaletteName::HighContrast => Palette {
+                good: Color::LightGreen,
+                bad: Color::LightRed,
+                warn: Color::LightYellow,
+                price: Color::LightMagenta,
+                total: Color::White,
+                info: Color::LightCyan,
+                key: Color::LightYellow,
+            },
+            // Okabe-Ito colors, blue and orange instead of green and red
+            PaletteName::ColorblindSafe => Palette {
+                good: Color::Rgb(86, 180, 233),
+                bad: Color::Rgb(230, 159, 0),
+                warn: Color::Rgb(240, 228, 66),
+                price: Color::Rgb(204, 121, 167),
+                total: Color::Rgb(213, 94, 0),
+                info: Color::Rgb(0, 158, 115),
+                key: Color::Rgb(240, 228, 66),
+            },
+            PaletteName::Monochrome => Palette {
+                good: Color::Reset,
+                bad: Color::Reset,
+                warn: Color::Reset,
+                price: Color::Reset,
+                total: Color::Reset,
+                info: Color::Reset,
+                key: Color::Reset,
+            },
+        }
+    }
+}
+
+/// colors used for each kind of text in the TUI
+#[derive(Clone, Copy, Debug)]
+pub struct Palette {
+    pub good: Color,  // legal cards, loaded files, added cards
+    pub bad: Color,   // illegal cards, errors, removed cards
+    pub warn: Color,  // restricted cards, changed quantities
+    pub price: Color, // prices and percentages
+    pub total: Color, // totals
+    pub info: Color,  // highlighted tab, mana costs, counters
+    pub key: Color,   // key hints in the instructions
+}
+
+impl Palette {
+    /// LEGAL or NOT LEGAL, with a marker so it doesn't depend on color alone
+    pub fn legal_span(&self, legal: bool) -> Span<'static> {
+        if legal {
+            Span::from("✓ LEGAL").fg(self.good)
+        } else {
+            Span::from("✗ NOT LEGAL").fg(self.bad).bold()
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::{collections::HashMap, error::Error, path::Path};
+
+use csv::Writer;
+
+use crate::{
+    collection::CollectionCard,
+    database::{
+        history::PriceHistory,
+        scryfall::{
+            get_min_price, match_card, Legality, MtGColors, PriceType, ScryfallCard, FORMAT_NAMES,
+        },
+        sets::{owned_printing, ScryfallSet},
+    },
+};
+
+/// number of cards shown in the most valuable cards list by default
+pub const DEFAULT_TOP_NUM: usize = 10;
+
+/// a line item from the collection with its price attached
+#[derive(Clone, Debug)]
+pub struct ValuedCard {
+    pub name: String,
+    pub quantity: u64,
+    pub set: String,
+    pub rarity: String,
+    pub unit_price: f64,
+    pub total: f64,
+    pub cheapest: bool, // priced with the cheapest printing because its own wasn't found
+}
+
+/// total value of a collection along with a few different breakdowns
+/// every breakdown is sorted from most to least valuable
+#[derive(Clone, Debug)]
+pub struct CollectionValue {
+    pub currency: PriceType,
+    pub total: f64,
+    pub by_set: Vec<(String, f64)>,
+    pub by_rarity: Vec<(String, f64)>,
+    pub by_color: Vec<(String, f64)>,
+    pub by_format: Vec<(String, f64)>,
+    pub cards: Vec<ValuedCard>,
+    pub not_found: Vec<String>, // cards that couldn't be matched to the database
+    pub history: Vec<(u64, f64)>, // total value on each date in the price history
+}
+
+impl CollectionValue {
+    /// the most valuable line items in the collection
+    pub fn top(&self, num: usize) -> &[ValuedCard] {
+        &self.cards[..num.min(self.cards.len())]
+    }
+
+    /// line items priced with the cheapest printing of the card
+    pub fn cheapest_count(&self) -> usize {
+        self.cards.iter().filter(|c| c.cheapest).count()
+    }
+}
+
+/// prices every printing in the collection using the card database and totals it up
+/// printings is the collection before it is squashed, so the set of each printing is known
+/// each line is priced with its own printing and finish from the set index, or with the cheapest
+/// printing of the card when the set index doesn't have it
+pub async fn collection_value(
+    printings: &[CollectionCard],
+    database: &HashMap<String, ScryfallCard>,
+    sets: &HashMap<String, ScryfallSet>,
+    history: &PriceHistory,
+    currency: PriceType,
+) -> CollectionValue {
+    let mut total = 0.0;
+    let mut by_set: HashMap<String, f64> = HashMap::new();
+    let mut by_rarity: HashMap<String, f64> = HashMap::new();
+    let mut by_color: HashMap<String, f64> = HashMap::new();
+    let mut by_format: HashMap<String, f64> = HashMap::new();
+    let mut cards = Vec::new();
+    let mut not_found = Vec::new();
+    for card in printings {
+        let matched = match match_card(&card.name, database) {
+            Some(m) => m,
+            None => {
+                not_found.push(card.name.clone());
+                continue;
+            }
+        };
+        let owned = owned_printing(&card.edition, &card.collector_number, sets).and_then(|p| {
+            p.prices
+                .get_finish(&currency, &card.foil)
+                .map(|price| (price, p.rarity.to_string()))
+        });
+        let cheapest = owned.is_none();
+        let (unit_price, rarity) = owned.unwrap_or_else(|| {
+            (
+                get_min_price(std::slice::from_ref(&matched), currency.clone()),
+                matched.rarity.to_string(),
+            )
+        });
+        let card_total = unit_price * card.quantity as f64;
+        let set = if card.edition.is_empty() {
+            matched.set.to_uppercase()
+        } else {
+            card.edition.to_uppercase()
+        };
+        total += card_total;
+        *by_set.entry(set.clone()).or_insert(0.0) += card_total;
+        *by_rarity.entry(rarity.clone()).or_insert(0.0) += card_total;
+        *by_color
+            .entry(color_group(matched.face_colors()))
+            .or_insert(0.0) += card_total;
+        for format in FORMAT_NAMES {
+            if let Some(Legality::Legal | Legality::Restricted) = matched.legalities.get(format) {
+                *by_format.entry(format.to_string()).or_insert(0.0) += card_total;
+            }
+        }
+        cards.push(ValuedCard {
+            name: card.name.clone(),
+            quantity: card.quantity,
+            set,
+            rarity,
+            unit_price,
+            total: card_total,
+            cheapest,
+        });
+    }
+    cards.sort_by(|a, b| b.total.total_cmp(&a.total));
+    CollectionValue {
+        currency: currency.clone(),
+        total,
+        by_set: sorted_breakdown(by_set),
+        by_rarity: sorted_breakdown(by_rarity),
+        by_color: sorted_breakdown(by_color),
+        by_format: sorted_breakdown(by_format),
+        cards,
+        not_found,
+        history: history.collection_value(printings, &currency),
+    }
+}
+
+/// writes the full value report to a CSV file
+/// every breakdown is written as Category,Name,Quantity,Value rows, followed by every card
+pub fn write_value_csv(value: &CollectionValue, path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut writer = Writer::from_path(path)?;
+    writer.write_record(["Category", "Name", "Quantity", "Value"])?;
+    writer.write_record([
+        "Total".to_string(),
+        "Collection".to_string(),
+        String::new(),
+        format!("{:.2}", value.total),
+    ])?;
+    let breakdowns = [
+        ("Set", &value.by_set),
+        ("Rarity", &value.by_rarity),
+        ("Color", &value.by_color),
+        ("Format", &value.by_format),
+    ];
+    for (category, breakdown) in breakdowns {
+        for (name, amount) in breakdown.iter() {
+            writer.write_record([
+                category.to_string(),
+                name.clone(),
+                String::new(),
+                format!("{:.2}", amount),
+            ])?;
+        }
+    }
+    for card in value.cards.iter() {
+        let cheapest = if card.cheapest {
+            " [cheapest printing]"
+        } else {
+            ""
+        };
+        writer.write_record([
+            "Card".to_string(),
+            format!("{} ({}){}", card.name, card.set, cheapest),
+            card.quantity.to_string(),
+            format!("{:.2}", card.total),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// groups a card's colors into a single label for the color breakdown
+fn color_group(colors: Option<&Vec<MtGColors>>) -> String {
+    match colors {
+        Some(c) if c.len() == 1 => c[0].to_string(),
+        Some(c) if c.len() > 1 => "Multicolor".to_string(),
+        _ => "Colorless".to_string(),
+    }
+}
+
+fn sorted_breakdown(map: HashMap<String, f64>) -> Vec<(String, f64)> {
+    let mut breakdown: Vec<(String, f64)> = map.into_iter().collect();
+    breakdown.sort_by(|a, b| b.1.total_cmp(&a.1));
+    breakdown
+}
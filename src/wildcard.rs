@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use crate::{
+    collection::CollectionCard,
+    database::scryfall::{make_safe_name, GameFormat, MtGRarity, ScryfallCard, ScryfallSetType},
+};
+
+/// what a missing card costs to craft on Arena, in the order they are grouped on the Missing tab
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WildcardCost {
+    Mythic,
+    Rare,
+    Uncommon,
+    Common,
+    Free,          // basic lands
+    UnknownRarity, // on Arena, but the database doesn't know the rarity of the Arena printing
+    NotOnArena,
+    NotFound,
+}
+
+impl WildcardCost {
+    /// goes by the lowest rarity of the card's Arena printings
+    pub fn of(card: Option<&ScryfallCard>) -> Self {
+        let card = match card {
+            Some(c) => c,
+            None => return WildcardCost::NotFound,
+        };
+        if !card.in_game(&GameFormat::Arena) {
+            return WildcardCost::NotOnArena;
+        }
+        if card
+            .type_line
+            .as_ref()
+            .is_some_and(|t| t.contains("Basic") && t.contains("Land"))
+        {
+            return WildcardCost::Free;
+        }
+        // databases saved before Arena rarities were kept only know the printing they kept
+        let rarity = match &card.arena_rarity {
+            Some(r) => r,
+            None if card.games.contains(&GameFormat::Arena) => &card.rarity,
+            None => return WildcardCost::UnknownRarity,
+        };
+        match rarity {
+            MtGRarity::Common => WildcardCost::Common,
+            MtGRarity::Uncommon => WildcardCost::Uncommon,
+            MtGRarity::Rare => WildcardCost::Rare,
+            // special and bonus cards are crafted with mythic wildcards
+            _ => WildcardCost::Mythic,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WildcardCost::Mythic => "Mythic",
+            WildcardCost::Rare => "Rare",
+            WildcardCost::Uncommon => "Uncommon",
+            WildcardCost::Common => "Common",
+            WildcardCost::Free => "Free",
+            WildcardCost::UnknownRarity => "Rarity unknown",
+            WildcardCost::NotOnArena => "Not on Arena",
+            WildcardCost::NotFound => "Not found",
+        }
+    }
+}
+
+/// rebalanced "A-" cards and cards from Alchemy sets, they only exist on Arena
+pub fn is_alchemy(card: &ScryfallCard) -> bool {
+    card.name.starts_with("A-") || matches!(card.set_type, ScryfallSetType::Alchemy)
+}
+
+/// wildcards needed for the missing cards of a deck
+#[derive(Clone, Debug, Default)]
+pub struct WildcardTotal {
+    pub mythic: u64,
+    pub rare: u64,
+    pub uncommon: u64,
+    pub common: u64,
+    pub not_on_arena: u64,   // copies that can't be crafted, not counted above
+    pub unknown_rarity: u64, // copies without a known Arena rarity, not counted above
+    pub alchemy: u64,        // copies of Alchemy cards, counted above
+}
+
+impl WildcardTotal {
+    pub fn total(&self) -> u64 {
+        self.mythic + self.rare + self.uncommon + self.common
+    }
+}
+
+/// counts the wildcards needed to craft every missing copy
+pub fn wildcard_total(
+    missing: &[CollectionCard],
+    database: &HashMap<String, ScryfallCard>,
+) -> WildcardTotal {
+    let mut total = WildcardTotal::default();
+    for card in missing {
+        let matched = database.get(&make_safe_name(&card.name, true));
+        match WildcardCost::of(matched) {
+            WildcardCost::Mythic => total.mythic += card.quantity,
+            WildcardCost::Rare => total.rare += card.quantity,
+            WildcardCost::Uncommon => total.uncommon += card.quantity,
+            WildcardCost::Common => total.common += card.quantity,
+            WildcardCost::NotOnArena => total.not_on_arena += card.quantity,
+            WildcardCost::UnknownRarity => total.unknown_rarity += card.quantity,
+            WildcardCost::Free | WildcardCost::NotFound => {}
+        }
+        if matched.is_some_and(is_alchemy) {
+            total.alchemy += card.quantity;
+        }
+    }
+    total
+}